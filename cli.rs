@@ -0,0 +1,710 @@
+// code style: &String parameters
+#![allow(clippy::ptr_arg)]
+
+use std::env;
+use std::path::Path;
+use std::process;
+
+use imgdesk::error::ImgError;
+use imgdesk::format::Format;
+use imgdesk::format::FormatType;
+use imgdesk::format::archive_format;
+use imgdesk::format::entry::Entry;
+use imgdesk::keystore::Keystore;
+use imgdesk::utility;
+
+const USAGE : &str = "IMG Desk - command line
+
+Usage:
+  imgdesk <archive>                               List entries (same as list)
+  imgdesk list <archive>                          List entries: index, name, offset, size, and the uncompressed size for RPF,
+                                                  the streaming size and size in archive for IMG version 2, or the resource type and memory sizes for IMG version 3
+  imgdesk info <archive>                          Show archive version and layout
+  imgdesk extract <archive> <folder> [<name>...]  Export entries or archive folders to a folder (all when no names are given)
+  imgdesk add <archive> <file>...                 Add files as new entries (creates a version 1 archive if missing)
+  imgdesk replace <archive> <file>...             Replace entries with files of the same name
+  imgdesk import <archive> <folder> [<path>]      Add or replace entries from a folder and its sub folders, into an archive folder for RPF
+  imgdesk remove <archive> <name>...              Remove entries
+  imgdesk rename <archive> <name> <new name>      Rename an entry
+  imgdesk move <archive> <name> <index>           Move an entry to an index, from 1 to the entry count
+  imgdesk set-sizes <archive> <name> <size> <size in archive>
+                                                  Set the streaming size and size in archive of an IMG version 2 entry, in sectors of 2048 bytes
+  imgdesk rebuild <archive> [<archive out>]       Recalculate all entry offsets and save
+  imgdesk set-version <archive> <version>         Set the IMG version: 1, 2, 3 or 3-encrypted, or for RPF 2/3: the same version with or without -encrypted
+  imgdesk find-keys <executable>                  Find known keys in a game executable, e.g. GTAIV.exe, and add them to keys.txt
+
+Options:
+  --names <file>                                  Name list, one per line, for archives that store name hashes (RPF3, RPF6)
+  --keys <file>                                   Keys for encrypted archives, one name = hex per line (gta4, rpf3, rpf4, rpf6, gta5_aes, gta5_ng_keys, gta5_ng_tables)
+                                                  Keys in keys.txt in the IMG Desk data folder are always loaded
+  --compress                                      Deflate uncompressed binary entries when saving (RPF7)
+
+Commands that change the archive save it in place.";
+
+const COMMANDS : [&str; 12] = ["list", "info", "extract", "add", "replace", "import", "remove", "rename", "move", "set-sizes", "rebuild", "set-version"];
+
+fn main()
+{
+	let mut args : Vec<String> = env::args().skip(1).collect();
+	
+	// options
+	let names_path = get_option(&mut args, "--names");
+	let keys_path = get_option(&mut args, "--keys");
+	let compress = get_flag(&mut args, "--compress");
+	
+	if args.is_empty() || args[0] == "-h" || args[0] == "--help" || args[0] == "help"
+	{
+		println!("{}", USAGE);
+		return;
+	}
+	
+	// a lone archive path, e.g. from a file association
+	if args.len() == 1 && Path::new(&args[0]).is_file()
+	{
+		run(&String::from("list"), &args[0..1], &names_path, &keys_path, compress);
+		return;
+	}
+	
+	if args.len() < 2
+	{
+		fail(&format!("missing archive path for \"{}\"", args[0]));
+	}
+	
+	// the only command that takes a file other than an archive
+	if args[0] == "find-keys"
+	{
+		find_keys(&args[1]);
+		return;
+	}
+	
+	run(&args[0], &args[1..], &names_path, &keys_path, compress);
+}
+
+// an option with a value, removed from the arguments
+fn get_option(args: &mut Vec<String>, name: &str) -> Option<String>
+{
+	let i = args.iter().position(|arg| arg == name)?;
+	if i + 1 >= args.len()
+	{
+		fail(&format!("{} needs a file path", name));
+	}
+	
+	let value = args[i + 1].clone();
+	args.drain(i..i+2);
+	Some(value)
+}
+
+// an option without a value, removed from the arguments
+fn get_flag(args: &mut Vec<String>, name: &str) -> bool
+{
+	match args.iter().position(|arg| arg == name)
+	{
+		Some(i) =>
+		{
+			args.remove(i);
+			true
+		},
+		None => false
+	}
+}
+
+fn run(command: &String, args: &[String], names_path: &Option<String>, keys_path: &Option<String>, compress: bool)
+{
+	let img_path = &args[0];
+	let params = &args[1..];
+	
+	if !COMMANDS.contains(&command.as_str())
+	{
+		fail(&format!("unknown command \"{}\"", command));
+	}
+	
+	let mut format = if command == "add" && !Path::new(img_path).exists()
+	{
+		create(img_path)
+	}
+	else
+	{
+		open(img_path, names_path, keys_path)
+	};
+	format.compress_entries = compress;
+	
+	match command.as_str()
+	{
+		"list" => list(&mut format),
+		"info" => info(&mut format),
+		"extract" => extract(&mut format, params),
+		"add" => add(&mut format, params),
+		"replace" => replace(&mut format, params),
+		"import" => import(&mut format, params),
+		"remove" => remove(&mut format, params),
+		"rename" => rename(&mut format, params),
+		"move" => move_entry(&mut format, params),
+		"set-sizes" => set_sizes(&mut format, params),
+		"rebuild" => rebuild(&mut format, params),
+		"set-version" => set_version(&mut format, params),
+		_ => {}
+	}
+	
+	format.remove_temp_dir();
+}
+
+// open/save
+fn open(img_path: &String, names_path: &Option<String>, keys_path: &Option<String>) -> Format
+{
+	if !Path::new(img_path).is_file()
+	{
+		fail(&format!("no archive found at {}", img_path));
+	}
+	
+	let dir_path = utility::replace_file_extension(img_path, "dir").unwrap();
+	
+	let mut format = Format::default();
+	load_config_keys(&mut format);
+	if let Some(names_path) = names_path
+	{
+		if let Err(error) = format.add_hash_names_from_file(names_path)
+		{
+			fail(&format!("unable to read names from {}: {}", names_path, error));
+		}
+	}
+	if let Some(keys_path) = keys_path
+	{
+		if let Err(error) = format.keys.add_from_file(keys_path)
+		{
+			fail(&format!("unable to read keys from {}: {}", keys_path, error));
+		}
+	}
+	
+	if let Err(error) = format.parse(img_path, &dir_path)
+	{
+		format.remove_temp_dir();
+		fail(&format!("unable to open {}: {}", img_path, error));
+	}
+	format
+}
+
+fn create(img_path: &String) -> Format
+{
+	let dir_path = utility::replace_file_extension(img_path, "dir").unwrap();
+	
+	let mut format = Format::default();
+	load_config_keys(&mut format);
+	if let Err(error) = format.new(img_path, &dir_path)
+	{
+		fail(&format!("unable to create {}: {}", img_path, error));
+	}
+	format
+}
+
+// keys from the config file in the user's data folder
+fn load_config_keys(format: &mut Format)
+{
+	if let Err(error) = format.keys.add_from_config_file()
+	{
+		fail(&format!("unable to read keys from {}: {}", get_config_path(), error));
+	}
+}
+
+// for messages, the file name alone when there is no data folder
+fn get_config_path() -> String
+{
+	Keystore::get_config_path().unwrap_or_else(|| String::from("keys.txt"))
+}
+
+fn save(format: &mut Format, img_path_out: &String)
+{
+	check_entry_offsets(format);
+	
+	let dir_path_out = utility::replace_file_extension(img_path_out, "dir").unwrap();
+	let result = format.save(img_path_out, &dir_path_out);
+	check(format, result, &format!("unable to save {}", img_path_out));
+	
+	println!("Saved {}", img_path_out);
+}
+
+fn check_entry_offsets(format: &mut Format)
+{
+	let min_offset = format.get_entry_data_offset();
+	let entries : Vec<Entry> = format.get_entries_sorted_by_offset_out();
+	
+	let mut end_offset = min_offset;
+	for entry in entries.iter()
+	{
+		if entry.offset_out < end_offset
+		{
+			format.recalculate_entry_offsets();
+			println!("Recalculated all entry offsets.");
+			return;
+		}
+		end_offset = entry.offset_out + entry.size;
+	}
+}
+
+// commands
+fn list(format: &mut Format)
+{
+	for entry in format.entries.iter()
+	{
+		if entry.rpf.is_some()
+		{
+			println!("{}\t{}\t{}\t{}\t{}", entry.index + 1, get_entry_name(entry), entry.offset_out, entry.size, entry.get_uncompressed_size());
+		}
+		else if format.format_type == FormatType::IMG && format.img_version == 2
+		{
+			println!("{}\t{}\t{}\t{}\t{}\t{}", entry.index + 1, get_entry_name(entry), entry.offset_out, entry.size, entry.streaming_size, entry.size_in_archive);
+		}
+		else if format.supports_resource_types()
+		{
+			println!("{}\t{}\t{}\t{}\t{}", entry.index + 1, get_entry_name(entry), entry.offset_out, entry.size, entry.get_resource_text());
+		}
+		else
+		{
+			println!("{}\t{}\t{}\t{}", entry.index + 1, get_entry_name(entry), entry.offset_out, entry.size);
+		}
+	}
+}
+
+fn info(format: &mut Format)
+{
+	let img_file_size = utility::get_file_size(format.img_path_in.clone());
+	
+	let archive_format = format.get_archive_format().unwrap();
+	
+	println!("Path: {}", format.img_path_in);
+	if archive_format.needs_dir_file()
+	{
+		println!("DIR path: {}", format.dir_path_in);
+	}
+	println!("Format: {}", archive_format.get_name());
+	println!("Entries: {}", format.entries.len());
+	println!("Header size: {}", format.get_img_header_size());
+	println!("Directory size: {}", format.get_img_directory_size() + format.get_img_names_size());
+	println!("Entry data offset: {}", format.get_entry_data_offset());
+	println!("File size: {}", img_file_size);
+}
+
+fn extract(format: &mut Format, params: &[String])
+{
+	if params.is_empty()
+	{
+		fail("missing folder to extract to");
+	}
+	
+	let folder_path = &params[0];
+	let entry_names = &params[1..];
+	
+	if !Path::new(folder_path).is_dir()
+	{
+		fail(&format!("no folder found at {}", folder_path));
+	}
+	
+	let mut entries : Vec<Entry> = Vec::new();
+	if entry_names.is_empty()
+	{
+		entries = format.entries.clone();
+	}
+	else
+	{
+		for entry_name in entry_names
+		{
+			// a folder in the archive exports everything in it
+			if format.get_entry_index_by_path(entry_name).is_none()
+			{
+				if let Some(folder) = format.get_entry_tree().get_folder(entry_name)
+				{
+					for entry_index in folder.get_entry_indices_recursive()
+					{
+						entries.push(format.entries[entry_index].clone());
+					}
+					continue;
+				}
+			}
+			
+			entries.push(get_entry_by_name(format, entry_name));
+		}
+	}
+	
+	for mut entry in entries
+	{
+		let result = format.export_entry(folder_path, &mut entry);
+		check(format, result, &format!("unable to extract {}", get_entry_name(&entry)));
+	}
+}
+
+fn add(format: &mut Format, params: &[String])
+{
+	if params.is_empty()
+	{
+		fail("missing files to add");
+	}
+	
+	for file_path in params
+	{
+		if !Path::new(file_path).is_file()
+		{
+			fail(&format!("no file found at {}", file_path));
+		}
+		
+		let result = format.add_file(file_path.to_string());
+		let entry = check(format, result, &format!("unable to add {}", file_path));
+		println!("Added {}", get_entry_name(&entry));
+	}
+	
+	format.reassign_entry_indices();
+	
+	let img_path = format.img_path_in.clone();
+	save(format, &img_path);
+}
+
+fn replace(format: &mut Format, params: &[String])
+{
+	if params.is_empty()
+	{
+		fail("missing files to replace with");
+	}
+	
+	for file_path in params
+	{
+		if !Path::new(file_path).is_file()
+		{
+			fail(&format!("no file found at {}", file_path));
+		}
+		
+		let file_name = utility::get_file_name(file_path).unwrap().to_string();
+		if format.get_entry_count_by_name(file_name.clone()) != 1
+		{
+			fail(&format!("cannot replace file, entry name \"{}\" not found exactly once", file_name));
+		}
+		
+		let result = format.replace_file(file_path.to_string());
+		let entry = check(format, result, &format!("unable to replace with {}", file_path));
+		println!("Replaced {}", get_entry_name(&entry));
+	}
+	
+	let img_path = format.img_path_in.clone();
+	save(format, &img_path);
+}
+
+fn import(format: &mut Format, params: &[String])
+{
+	if params.is_empty()
+	{
+		fail("missing folder to import");
+	}
+	
+	let folder_path = &params[0];
+	if !Path::new(folder_path).is_dir()
+	{
+		fail(&format!("no folder found at {}", folder_path));
+	}
+	
+	let archive_folder_path = if params.len() > 1 { params[1].clone() } else { String::from("") };
+	
+	let result = format.import_folder(folder_path, &archive_folder_path);
+	let entries = check(format, result, &format!("unable to import {}", folder_path));
+	for entry in entries
+	{
+		println!("Imported {}", get_entry_name(&entry));
+	}
+	
+	let img_path = format.img_path_in.clone();
+	save(format, &img_path);
+}
+
+fn find_keys(exe_path: &String)
+{
+	if !Path::new(exe_path).is_file()
+	{
+		fail(&format!("no file found at {}", exe_path));
+	}
+	
+	let mut keys = Keystore::default();
+	let names = match keys.add_from_executable(exe_path)
+	{
+		Ok(names) => names,
+		Err(error) => fail(&format!("unable to read {}: {}", exe_path, error))
+	};
+	
+	if names.is_empty()
+	{
+		fail(&format!("no known keys found in {}", exe_path));
+	}
+	
+	for name in names
+	{
+		if let Err(error) = Keystore::add_to_config_file(&name, keys.get(&name).unwrap())
+		{
+			fail(&format!("unable to write {}: {}", get_config_path(), error));
+		}
+		println!("Found {}, saved to {}", name, get_config_path());
+	}
+}
+
+fn remove(format: &mut Format, params: &[String])
+{
+	if params.is_empty()
+	{
+		fail("missing entry names to remove");
+	}
+	
+	for entry_name in params
+	{
+		let entry = get_entry_by_name(format, entry_name);
+		let result = format.remove(&entry);
+		check(format, result, &format!("unable to remove {}", entry_name));
+		println!("Removed {}", entry_name);
+	}
+	
+	format.reassign_entry_indices();
+	
+	let img_path = format.img_path_in.clone();
+	save(format, &img_path);
+}
+
+fn rename(format: &mut Format, params: &[String])
+{
+	if params.len() != 2
+	{
+		fail("rename needs an entry name and a new name");
+	}
+	
+	let new_entry_name = params[1].trim().to_string();
+	let result = format.check_entry_name(&new_entry_name);
+	check(format, result, &format!("unable to rename {}", params[0]));
+	
+	let entry = get_entry_by_name(format, &params[0]);
+	format.entries[entry.index as usize].set_name(&new_entry_name);
+	println!("Renamed {} to {}", params[0], new_entry_name);
+	
+	let img_path = format.img_path_in.clone();
+	save(format, &img_path);
+}
+
+fn move_entry(format: &mut Format, params: &[String])
+{
+	if params.len() != 2
+	{
+		fail("move needs an entry name and a new index");
+	}
+	
+	let entry_count = format.entries.len();
+	let new_entry_index = match params[1].trim().replace(",", "").parse::<usize>()
+	{
+		Ok(new_entry_index) if new_entry_index >= 1 && new_entry_index <= entry_count => new_entry_index,
+		_ => fail(&format!("index \"{}\" must be between 1 and {}", params[1], entry_count))
+	};
+	
+	let entry = get_entry_by_name(format, &params[0]);
+	format.set_entry_index(entry.index as u64, (new_entry_index - 1) as u64);
+	println!("Moved {} to #{}", params[0], new_entry_index);
+	
+	let img_path = format.img_path_in.clone();
+	save(format, &img_path);
+}
+
+fn set_sizes(format: &mut Format, params: &[String])
+{
+	if params.len() != 3
+	{
+		fail("set-sizes needs an entry name, a streaming size and a size in archive");
+	}
+	
+	if format.format_type != FormatType::IMG || format.img_version != 2
+	{
+		fail("entry sizes can only be set for IMG version 2");
+	}
+	
+	let mut sizes : Vec<u64> = Vec::with_capacity(2);
+	for param in params[1..].iter()
+	{
+		match param.trim().parse::<u16>()
+		{
+			Ok(sectors) => sizes.push(utility::sectors_to_bytes(sectors as u64)),
+			Err(_) => fail(&format!("size \"{}\" must be 0-65535 sectors", param))
+		}
+	}
+	
+	let entry = get_entry_by_name(format, &params[0]);
+	format.set_entry_sizes(entry.index as u64, sizes[0], sizes[1]);
+	println!("Set sizes of {} to {} and {} sectors", params[0], params[1].trim(), params[2].trim());
+	
+	let img_path = format.img_path_in.clone();
+	save(format, &img_path);
+}
+
+fn rebuild(format: &mut Format, params: &[String])
+{
+	let img_path_out = if params.is_empty()
+	{
+		format.img_path_in.clone()
+	}
+	else
+	{
+		params[0].clone()
+	};
+	
+	format.recalculate_entry_offsets();
+	println!("Recalculated all entry offsets.");
+	
+	save(format, &img_path_out);
+}
+
+fn set_version(format: &mut Format, params: &[String])
+{
+	if params.len() != 1
+	{
+		fail("set-version needs a version: 1, 2, 3 or 3-encrypted");
+	}
+	
+	// RPF archives only switch TOC encryption
+	if format.format_type == FormatType::RPF
+	{
+		let rpf_version = format.img_version.to_string();
+		let encrypted = if params[0] == rpf_version
+		{
+			false
+		}
+		else if params[0] == format!("{}-encrypted", rpf_version)
+		{
+			true
+		}
+		else
+		{
+			fail(&format!("unknown RPF version \"{}\", expected {} or {}-encrypted", params[0], rpf_version, rpf_version));
+		};
+		
+		// not every RPF version can be saved with and without an encrypted TOC
+		let archive_format = match archive_format::get_archive_format(FormatType::RPF, format.img_version, encrypted)
+		{
+			Some(archive_format) => archive_format,
+			None => fail(&format!("RPF version {} can't be saved as \"{}\"", format.img_version, params[0]))
+		};
+		
+		format.set_encrypted(encrypted);
+		println!("RPF version set to {}", archive_format.get_name());
+		
+		let img_path = format.img_path_in.clone();
+		save(format, &img_path);
+		return;
+	}
+	
+	let (img_version, img_encrypted) = match params[0].as_str()
+	{
+		"1" => (1, false),
+		"2" => (2, false),
+		"3" => (3, false),
+		"3-encrypted" => (3, true),
+		_ =>
+		{
+			fail(&format!("unknown IMG version \"{}\", expected 1, 2, 3 or 3-encrypted", params[0]));
+		}
+	};
+	
+	let result = format.set_version(img_version, img_encrypted);
+	check(format, result, "unable to set the IMG version");
+	println!("IMG version set to {}", get_img_version_text(img_version, img_encrypted));
+	
+	let img_path = format.img_path_in.clone();
+	save(format, &img_path);
+}
+
+// entries
+fn get_entry_by_name(format: &mut Format, entry_name: &String) -> Entry
+{
+	match format.get_entry_by_name(entry_name.clone())
+	{
+		Some(entry) => entry.clone(),
+		None => fail(&format!("no entry named \"{}\"", entry_name))
+	}
+}
+
+fn get_entry_name(entry: &Entry) -> String
+{
+	entry.get_path()
+}
+
+// output
+fn get_img_version_text(img_version: u8, img_encrypted: bool) -> String
+{
+	let img_encrypted_text = if img_version == 3
+	{
+		if img_encrypted
+		{
+			" (Encrypted)"
+		}
+		else
+		{
+			" (Unencrypted)"
+		}
+	}
+	else
+	{
+		""
+	};
+	format!("{}{}", img_version, img_encrypted_text)
+}
+
+fn check<T>(format: &mut Format, result: Result<T, ImgError>, text: &str) -> T
+{
+	match result
+	{
+		Ok(value) => value,
+		Err(error) =>
+		{
+			format.remove_temp_dir();
+			fail(&format!("{}: {}", text, error));
+		}
+	}
+}
+
+fn fail(text: &str) -> !
+{
+	eprintln!("imgdesk: {}", text);
+	process::exit(1);
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use std::fs;
+	
+	fn run_command(command: &str, args: &[&String])
+	{
+		let args : Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+		run(&command.to_string(), &args, &None, &None, false);
+	}
+	
+	#[test]
+	fn move_entry_to_index()
+	{
+		let dir_path = std::env::temp_dir().join("imgdesk_test_cli_move");
+		let _ = fs::remove_dir_all(&dir_path);
+		fs::create_dir_all(&dir_path).unwrap();
+		
+		let img_path = dir_path.join("test.img").to_string_lossy().to_string();
+		let mut file_paths : Vec<String> = Vec::new();
+		for (name, byte) in [("a.txd", 1u8), ("b.dff", 2), ("c.col", 3)].iter()
+		{
+			let file_path = dir_path.join(name).to_string_lossy().to_string();
+			fs::write(&file_path, vec![*byte; 2048]).unwrap();
+			file_paths.push(file_path);
+		}
+		
+		let mut add_args = vec![&img_path];
+		add_args.extend(file_paths.iter());
+		run_command("add", &add_args);
+		run_command("move", &[&img_path, &String::from("c.col"), &String::from("1")]);
+		
+		let mut format = open(&img_path, &None, &None);
+		let names : Vec<String> = format.entries.iter().map(get_entry_name).collect();
+		assert_eq!(names, vec!["c.col", "a.txd", "b.dff"]);
+		for (entry, byte) in format.entries.clone().iter().zip([3u8, 1, 2].iter())
+		{
+			assert_eq!(format.get_entry_data(entry).unwrap(), vec![*byte; 2048]);
+		}
+		format.remove_temp_dir();
+		
+		let _ = fs::remove_dir_all(&dir_path);
+	}
+}
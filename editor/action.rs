@@ -8,6 +8,7 @@ pub enum ActionTypeId
 	RemoveEntries,
 	ReplaceEntries,
 	RenameEntry,
+	SetEntrySizes,
 	MoveEntry,
 	SetImgVersion,
 	RecalculateOffsets
@@ -44,6 +45,7 @@ pub struct ActionItem
 	pub remove: ActionRemove,
 	pub replace: ActionReplace,
 	pub rename: ActionRename,
+	pub set_sizes: ActionSetSizes,
 	pub _move: ActionMove,
 	pub set_img_version: ActionSetImgVersion,
 	pub recalculate_offsets: ActionRecalculateOffsets
@@ -60,6 +62,7 @@ impl Default for ActionItem
 			remove: ActionRemove { entries: Vec::new(), entry_offsets_were_recalculated: false, entry_offsets: Vec::new() },
 			replace: ActionReplace { entries: Vec::new(), entry_offsets_were_recalculated: false, entry_offsets: Vec::new() },
 			rename: ActionRename { entry_index: 0, old_name: String::from(""), new_name: String::from("") },
+			set_sizes: ActionSetSizes { entry_index: 0, old_offset: 0, old_size: 0, old_size_in_archive: 0, new_size: 0, new_size_in_archive: 0 },
 			_move: ActionMove { old_entry_index: 0, new_entry_index: 0 },
 			set_img_version: ActionSetImgVersion { old_img_version: 0, old_img_encrypted: false, new_img_version: 0, new_img_encrypted: false },
 			recalculate_offsets: ActionRecalculateOffsets { entry_offsets: Vec::new() }
@@ -89,6 +92,10 @@ impl ActionItem
 			{
 				super::get_editor().undo_rename_entry(&mut self.rename);
 			},
+			ActionTypeId::SetEntrySizes =>
+			{
+				super::get_editor().undo_set_entry_sizes(&mut self.set_sizes);
+			},
 			ActionTypeId::MoveEntry =>
 			{
 				super::get_editor().undo_move_entry(&mut self._move);
@@ -127,6 +134,10 @@ impl ActionItem
 			{
 				super::get_editor().redo_rename_entry(&mut self.rename);
 			},
+			ActionTypeId::SetEntrySizes =>
+			{
+				super::get_editor().redo_set_entry_sizes(&mut self.set_sizes);
+			},
 			ActionTypeId::MoveEntry =>
 			{
 				super::get_editor().redo_move_entry(&mut self._move);
@@ -209,6 +220,17 @@ pub struct ActionRename
 	pub new_name: String
 }
 
+#[derive(Default,Clone)]
+pub struct ActionSetSizes
+{
+	pub entry_index: u64,
+	pub old_offset: u64,
+	pub old_size: u64,
+	pub old_size_in_archive: u64,
+	pub new_size: u64,
+	pub new_size_in_archive: u64
+}
+
 #[derive(Default,Clone)]
 pub struct ActionMove
 {
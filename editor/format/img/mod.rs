@@ -1,5 +0,0 @@
-pub mod version1;
-pub mod version2;
-pub mod version3_encrypted;
-pub mod version3_unencrypted;
-
@@ -1,2 +0,0 @@
-pub mod version0;
-pub mod version2;
@@ -242,6 +242,13 @@ winres 0.1.11
 			width: Some(100),
 			text: "Size".to_string()
 		});
+		self.app.main_entries.insert_column(InsertListViewColumn
+		{
+			index: Some(4),
+			fmt: None,
+			width: Some(200),
+			text: "Resource".to_string()
+		});
 	}
 	
 	// img versions for combo box
@@ -295,7 +302,8 @@ winres 0.1.11
 		self.app.select_all.set_enabled(true);
 		self.app.select_inverse.set_enabled(true);
 		self.app.recalculate_offsets.set_enabled(true);
-		self.app.img_version_combo.set_enabled(true);
+		// RPF archives keep their version
+		self.app.img_version_combo.set_enabled(super::get_editor().format.format_type == super::format::FormatType::IMG);
 		self.app.undo.set_enabled(false);
 		self.app.redo.set_enabled(false);
 		
@@ -487,19 +495,21 @@ winres 0.1.11
 		};
 		
 		let index = (row_index + 1).to_formatted_string(&Locale::en);
-		let name = unsafe { super::vendor::str_from_u8_nul_utf8_unchecked(&entry.name) };
+		let name = entry.get_path();
 		let offset = entry.offset_out.to_formatted_string(&Locale::en);
 		let size = entry.size.to_formatted_string(&Locale::en);
+		let resource = entry.get_resource_text();
 		
 		self.app.main_entries.insert_item(InsertListViewItem{index:Some(row_index as i32), sub_item:Some(0), text:index.to_string()});
 		self.app.main_entries.insert_sub_item(InsertListViewItem{index:Some(row_index as i32), sub_item:Some(1), text:name.to_string()});
 		self.app.main_entries.insert_sub_item(InsertListViewItem{index:Some(row_index as i32), sub_item:Some(2), text:offset.to_string()});
 		self.app.main_entries.insert_sub_item(InsertListViewItem{index:Some(row_index as i32), sub_item:Some(3), text:size.to_string()});
+		self.app.main_entries.insert_sub_item(InsertListViewItem{index:Some(row_index as i32), sub_item:Some(4), text:resource});
 	}
 	
 	pub fn can_entry_be_shown(&mut self, entry: &Entry, has_include_text: bool, has_exclude_text: bool, include_text: &String, exclude_text: &String) -> bool
 	{
-		let name2 = unsafe { super::vendor::str_from_u8_nul_utf8_unchecked(&entry.name) };
+		let name2 = entry.get_path();
 		let name3 = name2.to_uppercase();
 		
 		if (!has_include_text || name3.contains(include_text)) && (!has_exclude_text || !name3.contains(exclude_text))
@@ -556,14 +566,16 @@ winres 0.1.11
 		let row = entry.index;
 		
 		let index = (entry.index + 1).to_formatted_string(&Locale::en);
-		let name = unsafe { super::vendor::str_from_u8_nul_utf8_unchecked(&entry.name) };
+		let name = entry.get_path();
 		let offset = entry.offset_out.to_formatted_string(&Locale::en);
 		let size = entry.size.to_formatted_string(&Locale::en);
+		let resource = entry.get_resource_text();
 		
 		self.app.main_entries.insert_sub_item(InsertListViewItem{index:Some(row as i32), sub_item:Some(0), text:index.to_string()});
 		self.app.main_entries.insert_sub_item(InsertListViewItem{index:Some(row as i32), sub_item:Some(1), text:name.to_string()});
 		self.app.main_entries.insert_sub_item(InsertListViewItem{index:Some(row as i32), sub_item:Some(2), text:offset.to_string()});
 		self.app.main_entries.insert_sub_item(InsertListViewItem{index:Some(row as i32), sub_item:Some(3), text:size.to_string()});
+		self.app.main_entries.insert_sub_item(InsertListViewItem{index:Some(row as i32), sub_item:Some(4), text:resource});
 	}
 	
 	pub fn update_entries_past_entry(&mut self, entry: &Entry)
@@ -720,6 +732,9 @@ pub struct BasicApp
 	#[nwg_control(text: "Credits", size: (50, 25), position: (620, 190), font: Some(&data.main_font))]
     pub credits: nwg::Button,
 	
+	#[nwg_control(text: "Sizes", size: (70, 25), position: (520, 225), font: Some(&data.main_font))]
+    pub set_sizes: nwg::Button,
+	
 	
 	
 	
@@ -785,7 +800,7 @@ pub struct BasicApp
 	
 	
 	
-	#[nwg_control(text: "", size: (200, 15*10), position: (520, 230), font: Some(&data.main_font))]
+	#[nwg_control(text: "", size: (200, 15*9), position: (520, 260), font: Some(&data.main_font))]
     pub entry_extension_counts: nwg::Label,
 	
 	
@@ -1,8 +1,9 @@
-pub mod format;
 pub mod gui;
 pub mod action;
-pub mod utility;
-pub mod vendor;
+
+pub use imgdesk::format;
+pub use imgdesk::utility;
+pub use imgdesk::vendor;
 
 extern crate native_windows_gui as nwg;
 extern crate native_windows_derive as nwd;
@@ -34,6 +35,8 @@ use winapi::um::commctrl::NMLISTVIEW;
 use action::*;
 use format::img as img;
 use format::entry::Entry as Entry;
+use imgdesk::error::ImgError;
+use imgdesk::keystore::Keystore;
 
 
 
@@ -121,10 +124,23 @@ impl Editor
 		}
 		
 		let file_name = String::from("new.img");
-		let img_path_in : String = utility::get_next_file_path2(self.format.get_new_dir(), file_name.clone());
+		let new_dir = match self.format.get_new_dir()
+		{
+			Ok(new_dir) => new_dir,
+			Err(error) =>
+			{
+				self.show_error("Cannot Create IMG", &format!("Cannot create {}", file_name), &error);
+				return;
+			}
+		};
+		let img_path_in : String = utility::get_next_file_path2(new_dir, file_name.clone());
 		let dir_path_in = utility::replace_file_extension(&img_path_in, "dir").unwrap();
 		
-		self.format.new(&img_path_in, &dir_path_in);
+		if let Err(error) = self.format.new(&img_path_in, &dir_path_in)
+		{
+			self.show_error("Cannot Create IMG", &format!("Cannot create {}", img_path_in), &error);
+			return;
+		}
 		
 		self.on_file_open();
 		self.set_edited(true);
@@ -143,7 +159,7 @@ impl Editor
 		self.open_directly(img_path_in);
 	}
 	
-	fn open_directly(&mut self, img_path_in: String)
+	pub fn open_directly(&mut self, img_path_in: String)
 	{
 		if self.is_open()
 		{
@@ -155,7 +171,19 @@ impl Editor
 		
 		let dir_path_in = utility::replace_file_extension(&img_path_in, "dir").unwrap();
 		
-		self.format.parse(&img_path_in, &dir_path_in);
+		if let Err(error) = self.format.keys.add_from_config_file()
+		{
+			self.show_error("Cannot Open IMG", &format!("Cannot read keys from {}", Keystore::get_config_path().unwrap_or_else(|| String::from("keys.txt"))), &error);
+			self.on_no_file_open();
+			return;
+		}
+		
+		if let Err(error) = self.format.parse(&img_path_in, &dir_path_in)
+		{
+			self.show_error("Cannot Open IMG", &format!("Cannot open {}", img_path_in), &error);
+			self.on_no_file_open();
+			return;
+		}
 		
 		self.on_file_open();
 		
@@ -189,26 +217,32 @@ impl Editor
 		self.open_directly(img_path_in);
 	}
 	
-	pub fn save(&mut self)
+	pub fn save(&mut self) -> bool
 	{
 		if !self.check_if_can_save()
 		{
-			return;
+			return false;
 		}
 		
 		if !self.gui.app.save_dialog.run(Some(&self.gui.app.window))
 		{
-			return;
+			return false;
 		}
 		
 		let img_path_out = self.gui.app.save_dialog.get_selected_item().unwrap();
 		let dir_path_out = utility::replace_file_extension(&img_path_out, "dir").unwrap();
 		
-		self.format.save(&img_path_out, &dir_path_out);
+		if let Err(error) = self.format.save(&img_path_out, &dir_path_out)
+		{
+			self.show_error("Cannot Save IMG", &format!("Cannot save {}", img_path_out), &error);
+			return false;
+		}
 		
 		self.log(&format!("Saved IMG {}", utility::get_file_name(&img_path_out).unwrap()));
 		
 		self.set_edited(false);
+		
+		true
 	}
 	
 	pub fn add(&mut self)
@@ -227,7 +261,7 @@ impl Editor
 			/*
 			for new_entry in new_entries.iter()
 			{
-				entry_offsets.push(new_entry.offset_out as u64);
+				entry_offsets.push(new_entry.offset_out);
 			}
 			*/
 			
@@ -309,7 +343,12 @@ impl Editor
 				if self.gui.app.main_entries.is_item_selected(i as i32)
 				{
 					let mut entry : Entry = self.format.entries[i].clone();
-					self.format.export_entry(folder_path.as_str(), &mut entry);
+					if let Err(error) = self.format.export_entry(folder_path.as_str(), &mut entry)
+					{
+						let entry_name = entry.name.clone();
+						self.show_error("Cannot Export Entry", &format!("Cannot export entry \"{}\"", entry_name), &error);
+						continue;
+					}
 					
 					exported_entries += 1;
 				}
@@ -342,7 +381,7 @@ impl Editor
 			/*
 			for new_entry in new_entries.iter()
 			{
-				entry_offsets.push(new_entry.offset_out as u64);
+				entry_offsets.push(new_entry.offset_out);
 			}
 			*/
 			let mut entry_offsets = self.format.get_entry_offsets();
@@ -443,7 +482,7 @@ impl Editor
 		
 		self.add_action_recalculate_offsets();
 		
-		self.format.recalculate_entry_offsets();
+		self.recalculate_entry_offsets();
 		
 		self.log(&format!("Recalculated all entry offsets."));
 		
@@ -491,10 +530,7 @@ impl Editor
 		
 		let selected_entry_copy = selected_entries[0].clone();
 		
-		let current_entry_name = unsafe
-		{
-			vendor::str_from_u8_nul_utf8_unchecked(&selected_entry_copy.name)
-		};
+		let current_entry_name = selected_entry_copy.name.as_str();
 		
 		let mut new_entry_name : String = self.gui.show_text_input_window("Rename Entry", "Choose a new name for the entry.", current_entry_name);
 		if new_entry_name.len() == 0
@@ -503,12 +539,35 @@ impl Editor
 		}
 		
 		new_entry_name = new_entry_name.trim().to_string();
-		if new_entry_name.len() > 24
+		if let Err(error) = self.format.check_entry_name(&new_entry_name)
+		{
+			self.show_error("Rename Not Applied", &format!("Cannot rename entry to \"{}\"", new_entry_name), &error);
+			return;
+		}
+		
+		//let mut selected_entry = &mut self.format.entries[selected_entry_copy.index as usize];
+		{
+			//let mut selected_entry_copy = &mut self.format.entries[selected_entry_copy.index as usize];
+			self.add_action_rename(&selected_entry_copy, &(current_entry_name.to_string()), &(new_entry_name.to_string()));
+		}
+		{
+			self.set_entry_name(selected_entry_copy.index as u64, &(new_entry_name.to_string()));
+		}
+		
+		self.log(&format!("Renamed entry to {}", new_entry_name));
+		
+		self.set_edited(true);
+	}
+	
+	pub fn set_sizes(&mut self)
+	{
+		let selected_entries = self.get_selected_entries();
+		if selected_entries.len() != 1
 		{
 			let params = nwg::MessageParams
 			{
-				title: "Rename Not Applied",
-				content: "New name for entry must be 1-24 characters in length.",
+				title: "Sizes Requirements Failed",
+				content: "Only one entry can be selected to set its sizes.",
 				buttons: nwg::MessageButtons::Ok,
 				icons: nwg::MessageIcons::Error
 			};
@@ -516,19 +575,50 @@ impl Editor
 			return;
 		}
 		
-		//let mut selected_entry = &mut self.format.entries[selected_entry_copy.index as usize];
+		if self.format.format_type != format::FormatType::IMG || self.format.img_version != 2
 		{
-			//let mut selected_entry_copy = &mut self.format.entries[selected_entry_copy.index as usize];
-			self.add_action_rename(&selected_entry_copy, &(current_entry_name.to_string()), &(new_entry_name.to_string()));
+			let params = nwg::MessageParams
+			{
+				title: "Sizes Requirements Failed",
+				content: "Entry sizes can only be set for IMG version 2.",
+				buttons: nwg::MessageButtons::Ok,
+				icons: nwg::MessageIcons::Error
+			};
+			self.gui.message(&params);
+			return;
 		}
+		
+		let selected_entry_copy = selected_entries[0].clone();
+		
+		// both sizes in sectors, e.g. "4 4"
+		let current_sizes = format!("{} {}", utility::to_sectors(selected_entry_copy.streaming_size), utility::to_sectors(selected_entry_copy.size_in_archive));
+		let new_sizes_text : String = self.gui.show_text_input_window("Set Entry Sizes", "Choose the streaming size and the size in archive, in sectors of 2048 bytes.", &current_sizes);
+		if new_sizes_text.len() == 0
 		{
-			let mut selected_entry = &mut self.format.entries[selected_entry_copy.index as usize];
-			selected_entry.set_name(&(new_entry_name.to_string()));
+			return;
 		}
 		
-		self.log(&format!("Renamed entry to {}", new_entry_name));
+		let new_sizes : Vec<u16> = new_sizes_text.split_whitespace().filter_map(|text| text.parse::<u16>().ok()).collect();
+		if new_sizes.len() != 2 || new_sizes_text.split_whitespace().count() != 2
+		{
+			let params = nwg::MessageParams
+			{
+				title: "Sizes Not Applied",
+				content: "Enter two sizes of 0-65535 sectors, separated by a space.",
+				buttons: nwg::MessageButtons::Ok,
+				icons: nwg::MessageIcons::Error
+			};
+			self.gui.message(&params);
+			return;
+		}
 		
-		self.set_edited(true);
+		let new_size = utility::sectors_to_bytes(new_sizes[0] as u64);
+		let new_size_in_archive = utility::sectors_to_bytes(new_sizes[1] as u64);
+		
+		self.add_action_set_sizes(&selected_entry_copy, new_size, new_size_in_archive);
+		self.set_entry_sizes(selected_entry_copy.index as u64, new_size, new_size_in_archive);
+		
+		self.log(&format!("Set sizes to {} and {} sectors", new_sizes[0], new_sizes[1]));
 	}
 	
 	pub fn _move(&mut self)
@@ -620,16 +710,11 @@ impl Editor
 		}
 		
 		{
-			let mut selected_entry = &mut self.format.entries[selected_entry_index as usize];
-			selected_entry.set_index((new_entry_index - 1) as u64);
+			self.set_entry_index(selected_entry_index as u64, (new_entry_index - 1) as u64);
 		}
 		
 		
-		let entry_name = unsafe
-		{
-			let selected_entry = &self.format.entries[selected_entry_copy.index as usize];
-			vendor::str_from_u8_nul_utf8_unchecked(&selected_entry.name)
-		};
+		let entry_name = self.format.entries[selected_entry_copy.index as usize].name.clone();
 		self.log(&format!("Moved {} to #{}", entry_name, new_entry_index));
 		
 		self.set_edited(true);
@@ -649,7 +734,7 @@ impl Editor
 			_ => (1, false)
 		};
 		
-		self.format.set_version(img_version, img_encrypted);
+		self.set_format_version(img_version, img_encrypted);
 		
 		self.add_action_set_img_version(current_img_version, current_img_encrypted, img_version, img_encrypted);
 		
@@ -677,6 +762,21 @@ impl Editor
 		self.gui.app.log.set_scroll_pos(log_line_count);
 	}
 	
+	// errors
+	fn show_error(&mut self, title: &str, text: &str, error: &ImgError)
+	{
+		self.log(&format!("{}: {}", text, error));
+		
+		let params = nwg::MessageParams
+		{
+			title: title,
+			content: &format!("{}.\r\n\r\n{}", text, error),
+			buttons: nwg::MessageButtons::Ok,
+			icons: nwg::MessageIcons::Error
+		};
+		self.gui.message(&params);
+	}
+	
 	// edited
 	fn set_edited(&mut self, edited: bool)
 	{
@@ -713,7 +813,10 @@ impl Editor
 			{
 				nwg::MessageChoice::Yes =>
 				{
-					self.save();
+					if !self.save()
+					{
+						return false;
+					}
 				},
 				nwg::MessageChoice::No => {},
 				nwg::MessageChoice::Cancel =>
@@ -797,7 +900,16 @@ impl Editor
 				entry_names[i].clone()
 			};
 			
-			let entry = self.format.add_file_at(file_path.to_string(), entry_index, entry_name);
+			let entry = match self.format.add_file_at(file_path.to_string(), entry_index, entry_name)
+			{
+				Ok(entry) => entry,
+				Err(error) =>
+				{
+					self.show_error("Cannot Add File", &format!("Cannot add file \"{}\"", file_path), &error);
+					i += 1;
+					continue;
+				}
+			};
 			
 			if ensure_visible_item_index == -1i64
 			{
@@ -854,7 +966,10 @@ impl Editor
 		
 		for entry in entries.iter()
 		{
-			self.format.remove(&entry);
+			if let Err(error) = self.format.remove(&entry)
+			{
+				self.show_error("Cannot Remove Entry", &format!("Cannot remove entry \"{}\"", entry.name), &error);
+			}
 		}
 		
 		self.on_remove_entries();
@@ -889,19 +1004,19 @@ impl Editor
 	
 	fn does_an_entry_overlap_header(&mut self) -> bool
 	{
-		let min_offset = self.format.get_img_header_size() as u32;
+		let min_offset = self.format.get_img_header_size();
 		self.format.entries.iter().filter(|&e| e.offset_out < min_offset).count() > 0
 	}
 	
 	fn does_an_entry_overlap_directory(&mut self) -> bool
 	{
-		let min_offset = (self.format.get_img_header_size() + self.format.get_img_directory_size()) as u32;
+		let min_offset = self.format.get_img_header_size() + self.format.get_img_directory_size();
 		self.format.entries.iter().filter(|&e| e.offset_out < min_offset).count() > 0
 	}
 	
 	fn is_any_entry_offset_invalid(&mut self) -> bool
 	{
-		let min_offset = self.format.get_entry_data_offset() as u32;
+		let min_offset = self.format.get_entry_data_offset();
 		self.format.entries.iter().filter(|&e| e.offset_out < min_offset).count() > 0
 	}
 	
@@ -970,7 +1085,7 @@ impl Editor
 				_ => {}
 			}
 			
-			self.format.recalculate_entry_offsets();
+			self.recalculate_entry_offsets();
 			
 			return true;
 		}
@@ -1101,7 +1216,16 @@ impl Editor
 				entry_indices[i as usize]
 			};
 			
-			let entry = self.format.replace_file_at(file_path.to_string(), entry_index, entry_name);
+			let entry = match self.format.replace_file_at(file_path.to_string(), entry_index, entry_name)
+			{
+				Ok(entry) => entry,
+				Err(error) =>
+				{
+					self.show_error("Cannot Replace File", &format!("Cannot replace file \"{}\"", file_name), &error);
+					i += 1;
+					continue;
+				}
+			};
 			
 			replaced_entry_count += 1;
 			
@@ -1205,15 +1329,28 @@ impl Editor
 	
 	fn add_undo_file(&mut self, entry: &Entry, action_item: &mut ActionReplaceEntry)
 	{
-		let entry_name = unsafe
+		let entry_name = entry.name.clone();
+		let data_undo_path : String = match Editor::save_undo_data(&mut self.format, &entry)
 		{
-			vendor::str_from_u8_nul_utf8_unchecked(&entry.name).to_string()
+			Ok(data_undo_path) => data_undo_path,
+			Err(error) =>
+			{
+				self.show_error("Cannot Save Undo Data", &format!("Cannot save undo data for entry \"{}\"", entry_name), &error);
+				String::from("")
+			}
 		};
-		let data_undo_path : String = utility::get_next_file_path2(self.format.get_undo_dir(), entry_name.clone());
-		utility::set_file_data(data_undo_path.clone(), &self.format.get_entry_data(&entry));
 		action_item.data_undo_path = data_undo_path;
 	}
 	
+	// the entry's data in a new file in the undo folder, returns the file's path
+	fn save_undo_data(format: &mut format::Format, entry: &Entry) -> Result<String, ImgError>
+	{
+		let data_undo_path : String = utility::get_next_file_path2(format.get_undo_dir()?, utility::get_data_file_name(&entry.name));
+		let data = format.get_entry_data(entry)?;
+		utility::set_file_data(data_undo_path.clone(), &data)?;
+		Ok(data_undo_path)
+	}
+	
 	fn remove_undo_file(&mut self, entry: &Entry)
 	{
 		//fs::remove_file(entry.data_undo_path.as_str());
@@ -1246,6 +1383,8 @@ impl Editor
 	{
 		self.add_action_before(_type);
 		
+		let mut undo_errors : Vec<(String, ImgError)> = Vec::new();
+		
 		let mut action = self.action_history.actions.last_mut().unwrap();
 		
 		let e = action._type;
@@ -1261,14 +1400,18 @@ impl Editor
 				};
 				for entry in entries
 				{
-					let entry_name = unsafe
+					let entry_name = entry.name.clone();
+					
+					let data_undo_path : String = match Editor::save_undo_data(&mut self.format, &entry)
 					{
-						vendor::str_from_u8_nul_utf8_unchecked(&entry.name).to_string()
+						Ok(data_undo_path) => data_undo_path,
+						Err(error) =>
+						{
+							undo_errors.push((entry_name.clone(), error));
+							String::from("")
+						}
 					};
 					
-					let data_undo_path : String = utility::get_next_file_path2(self.format.get_undo_dir(), entry_name.clone());
-					utility::set_file_data(data_undo_path.clone(), &self.format.get_entry_data(&entry));
-					
 					action.add.entries.push(ActionAddEntry
 					{
 						entry_index: entry.index as u64,
@@ -1287,14 +1430,18 @@ impl Editor
 				};
 				for entry in entries
 				{
-					let entry_name = unsafe
+					let entry_name = entry.name.clone();
+					
+					let data_undo_path : String = match Editor::save_undo_data(&mut self.format, &entry)
 					{
-						vendor::str_from_u8_nul_utf8_unchecked(&entry.name).to_string()
+						Ok(data_undo_path) => data_undo_path,
+						Err(error) =>
+						{
+							undo_errors.push((entry_name.clone(), error));
+							String::from("")
+						}
 					};
 					
-					let data_undo_path : String = utility::get_next_file_path2(self.format.get_undo_dir(), entry_name.clone());
-					utility::set_file_data(data_undo_path.clone(), &self.format.get_entry_data(&entry));
-					
 					action.remove.entries.push(ActionRemoveEntry
 					{
 						entry_index: entry.index as u64,
@@ -1313,14 +1460,18 @@ impl Editor
 				};
 				for entry in entries
 				{
-					let entry_name = unsafe
+					let entry_name = entry.name.clone();
+					
+					let data_undo_path : String = match Editor::save_undo_data(&mut self.format, &entry)
 					{
-						vendor::str_from_u8_nul_utf8_unchecked(&entry.name).to_string()
+						Ok(data_undo_path) => data_undo_path,
+						Err(error) =>
+						{
+							undo_errors.push((entry_name.clone(), error));
+							String::from("")
+						}
 					};
 					
-					let data_undo_path : String = utility::get_next_file_path2(self.format.get_undo_dir(), entry_name.clone());
-					utility::set_file_data(data_undo_path.clone(), &self.format.get_entry_data(&entry));
-					
 					action.replace.entries.push(ActionReplaceEntry
 					{
 						entry_index: entry.index as u64,
@@ -1332,6 +1483,11 @@ impl Editor
 			_ => {}
 		}
 		
+		for (entry_name, error) in undo_errors.iter()
+		{
+			self.show_error("Cannot Save Undo Data", &format!("Cannot save undo data for entry \"{}\"", entry_name), error);
+		}
+		
 		self.add_action_after();
 	}
 	
@@ -1351,6 +1507,25 @@ impl Editor
 		self.add_action_after();
 	}
 	
+	fn add_action_set_sizes(&mut self, entry: &Entry, new_size: u64, new_size_in_archive: u64)
+	{
+		self.add_action_before(ActionTypeId::SetEntrySizes);
+		
+		let mut action = self.action_history.actions.last_mut().unwrap();
+		
+		action.set_sizes = ActionSetSizes
+		{
+			entry_index: entry.index as u64,
+			old_offset: entry.offset_out,
+			old_size: entry.streaming_size,
+			old_size_in_archive: entry.size_in_archive,
+			new_size: new_size,
+			new_size_in_archive: new_size_in_archive
+		};
+		
+		self.add_action_after();
+	}
+	
 	fn add_action_move(&mut self, entry: &Entry, old_entry_index: u64, new_entry_index: u64)
 	{
 		self.add_action_before(ActionTypeId::MoveEntry);
@@ -1409,7 +1584,7 @@ impl Editor
 		
 		//if action.entry_offsets_were_recalculated
 		{
-			self.format.set_entry_offsets(&action.entry_offsets);
+			self.set_entry_offsets(&action.entry_offsets);
 		}
 		
 		self.skip_prompt_for_recalculating_offsets = false;
@@ -1429,7 +1604,7 @@ impl Editor
 		
 		//if action.entry_offsets_were_recalculated
 		{
-			self.format.set_entry_offsets(&action.entry_offsets);
+			self.set_entry_offsets(&action.entry_offsets);
 		}
 		
 		self.skip_prompt_for_recalculating_offsets = false;
@@ -1465,7 +1640,7 @@ impl Editor
 		
 		//if action.entry_offsets_were_recalculated
 		{
-			self.format.set_entry_offsets(&action.entry_offsets);
+			self.set_entry_offsets(&action.entry_offsets);
 		}
 		
 		self.log(&format!("[Undo Replace] Restored {} {}", replaced_entry_count, if replaced_entry_count == 1 { "entry" } else { "entries" }));
@@ -1475,25 +1650,36 @@ impl Editor
 	
 	pub fn undo_rename_entry(&mut self, action: &ActionRename)
 	{
-		let mut entry = self.format.get_entry_by_index(action.entry_index).unwrap();
-		
-		entry.set_name(&action.old_name);
+		self.set_entry_name(action.entry_index, &action.old_name);
 		
 		self.log(&format!("[Undo Rename] Restored name {}", action.old_name));
 	}
 	
-	pub fn undo_move_entry(&mut self, action: &ActionMove)
+	pub fn undo_set_entry_sizes(&mut self, action: &ActionSetSizes)
 	{
-		let mut entry = self.format.get_entry_by_index(action.new_entry_index).unwrap();
+		let entry = &mut self.format.entries[action.entry_index as usize];
+		entry.offset_out = action.old_offset;
+		entry.streaming_size = action.old_size;
+		entry.size_in_archive = action.old_size_in_archive;
 		
-		entry.set_index(action.old_entry_index);
+		let entry = entry.clone();
+		self.gui.on_entry_change(&entry);
+		self.update_all_stats();
+		self.set_edited(true);
+		
+		self.log(&format!("[Undo Sizes] Restored sizes {} and {} sectors", utility::to_sectors(action.old_size), utility::to_sectors(action.old_size_in_archive)));
+	}
+	
+	pub fn undo_move_entry(&mut self, action: &ActionMove)
+	{
+		self.set_entry_index(action.new_entry_index, action.old_entry_index);
 		
 		self.log(&format!("[Undo Move] Restored index #{}", action.old_entry_index + 1));
 	}
 	
 	pub fn undo_set_img_version(&mut self, action: &ActionSetImgVersion)
 	{
-		self.format.set_version(action.old_img_version, action.old_img_encrypted);
+		self.set_format_version(action.old_img_version, action.old_img_encrypted);
 		
 		let img_version_text : String = self.gui.get_img_version_text(action.old_img_version, action.old_img_encrypted);
 		self.log(&format!("[Undo Version] Restored as {}", &img_version_text.to_owned()));
@@ -1501,7 +1687,7 @@ impl Editor
 	
 	pub fn undo_recalculate_offsets(&mut self, action: &ActionRecalculateOffsets)
 	{
-		self.format.set_entry_offsets(&action.entry_offsets);
+		self.set_entry_offsets(&action.entry_offsets);
 		
 		self.log(&format!("[Undo Recalculate Offsets]"));
 	}
@@ -1521,7 +1707,7 @@ impl Editor
 		
 		if self.entry_offsets_were_recalculated
 		{
-			self.format.recalculate_entry_offsets();
+			self.recalculate_entry_offsets();
 		}
 		
 		self.skip_prompt_for_recalculating_offsets = false;
@@ -1541,7 +1727,7 @@ impl Editor
 		
 		if self.entry_offsets_were_recalculated
 		{
-			self.format.recalculate_entry_offsets();
+			self.recalculate_entry_offsets();
 		}
 		
 		self.skip_prompt_for_recalculating_offsets = false;
@@ -1577,7 +1763,7 @@ impl Editor
 		
 		if self.entry_offsets_were_recalculated
 		{
-			self.format.recalculate_entry_offsets();
+			self.recalculate_entry_offsets();
 		}
 		
 		self.log(&format!("[Redo Replace] Restored {} {}", replaced_entry_count, if replaced_entry_count == 1 { "entry" } else { "entries" }));
@@ -1587,25 +1773,28 @@ impl Editor
 	
 	pub fn redo_rename_entry(&mut self, action: &ActionRename)
 	{
-		let mut entry = self.format.get_entry_by_index(action.entry_index).unwrap();
-		
-		entry.set_name(&action.new_name);
+		self.set_entry_name(action.entry_index, &action.new_name);
 		
 		self.log(&format!("[Redo Rename] Renamed to {}", action.old_name));
 	}
 	
-	pub fn redo_move_entry(&mut self, action: &ActionMove)
+	pub fn redo_set_entry_sizes(&mut self, action: &ActionSetSizes)
 	{
-		let mut entry = self.format.get_entry_by_index(action.old_entry_index).unwrap();
+		self.set_entry_sizes(action.entry_index, action.new_size, action.new_size_in_archive);
 		
-		entry.set_index(action.new_entry_index);
+		self.log(&format!("[Redo Sizes] Set sizes to {} and {} sectors", utility::to_sectors(action.new_size), utility::to_sectors(action.new_size_in_archive)));
+	}
+	
+	pub fn redo_move_entry(&mut self, action: &ActionMove)
+	{
+		self.set_entry_index(action.old_entry_index, action.new_entry_index);
 		
 		self.log(&format!("[Redo Move] Restored index #{}", action.new_entry_index + 1));
 	}
 	
 	pub fn redo_set_img_version(&mut self, action: &ActionSetImgVersion)
 	{
-		self.format.set_version(action.new_img_version, action.new_img_encrypted);
+		self.set_format_version(action.new_img_version, action.new_img_encrypted);
 		
 		let img_version_text : String = self.gui.get_img_version_text(action.new_img_version, action.new_img_encrypted);
 		self.log(&format!("[Redo Version] Restored as {}", &img_version_text.to_owned()));
@@ -1613,7 +1802,7 @@ impl Editor
 	
 	pub fn redo_recalculate_offsets(&mut self, action: &ActionRecalculateOffsets)
 	{
-		self.format.recalculate_entry_offsets();
+		self.recalculate_entry_offsets();
 		
 		self.log(&format!("[Redo Recalculate Offsets]"));
 	}
@@ -1693,12 +1882,14 @@ impl Editor
 	fn on_only_one_row_selected(&mut self)
 	{
 		self.gui.app.rename.set_enabled(true);
+		self.gui.app.set_sizes.set_enabled(true);
 		self.gui.app._move.set_enabled(true);
 	}
 	
 	fn on_only_one_row_not_selected(&mut self)
 	{
 		self.gui.app.rename.set_enabled(false);
+		self.gui.app.set_sizes.set_enabled(false);
 		self.gui.app._move.set_enabled(false);
 	}
 	
@@ -1780,6 +1971,58 @@ impl Editor
 		self.set_edited(true);
 	}
 	
+	// format changes
+	fn set_entry_name(&mut self, entry_index: u64, new_entry_name: &String)
+	{
+		let entry = &mut self.format.entries[entry_index as usize];
+		entry.set_name(new_entry_name);
+		
+		let entry = entry.clone();
+		self.gui.on_entry_change(&entry);
+		self.on_rename_entry();
+	}
+	
+	fn set_entry_sizes(&mut self, entry_index: u64, streaming_size: u64, size_in_archive: u64)
+	{
+		self.format.set_entry_sizes(entry_index, streaming_size, size_in_archive);
+		
+		let entry = self.format.entries[entry_index as usize].clone();
+		self.gui.on_entry_change(&entry);
+		self.update_all_stats();
+		self.set_edited(true);
+	}
+	
+	fn set_entry_index(&mut self, entry_index: u64, new_entry_index: u64)
+	{
+		self.format.set_entry_index(entry_index, new_entry_index);
+		
+		self.on_change_entry_index();
+		
+		self.gui.app.main_entries.ensure_visible(new_entry_index as i32);
+	}
+	
+	fn recalculate_entry_offsets(&mut self)
+	{
+		self.format.recalculate_entry_offsets();
+		self.on_entry_offsets_change();
+	}
+	
+	fn set_entry_offsets(&mut self, entry_offsets: &Vec<u64>)
+	{
+		self.format.set_entry_offsets(entry_offsets);
+		self.on_entry_offsets_change();
+	}
+	
+	fn set_format_version(&mut self, img_version: u8, img_encrypted: bool)
+	{
+		if let Err(error) = self.format.set_version(img_version, img_encrypted)
+		{
+			self.show_error("Cannot Set IMG Version", "Cannot set the IMG version", &error);
+			return;
+		}
+		self.on_img_version_change();
+	}
+	
 	// entry offsets
 	pub fn check_to_adjust_entry_offsets(&mut self)
 	{
@@ -1795,7 +2038,7 @@ impl Editor
 			return;
 		}
 		
-		let entry_data_offset = self.format.get_entry_data_offset() as u32;
+		let entry_data_offset = self.format.get_entry_data_offset();
 		let entries : Vec<crate::editor::format::entry::Entry> = self.format.get_entries_sorted_by_offset_out();
 		let _type = if entries.first().unwrap().offset_out < entry_data_offset
 		{
@@ -1869,7 +2112,7 @@ impl Editor
 			}
 		}
 		
-		self.format.recalculate_entry_offsets();
+		self.recalculate_entry_offsets();
 		self.entry_offsets_were_recalculated = true;
 		
 		self.log(&format!("Recalculated Offsets"));
@@ -2196,7 +2439,7 @@ impl Editor
 		let mut i = 0u64;
 		for mut entry in entries
 		{
-			if entry.offset_in as u64 >= img_file_size
+			if entry.offset_in >= img_file_size
 			{
 				return total_entry_count - i;
 			}
@@ -2229,7 +2472,7 @@ impl Editor
 		{
 			let entry = self.format.entries[i].clone();
 			
-			let name2 = unsafe { vendor::str_from_u8_nul_utf8_unchecked(&entry.name) };
+			let name2 = entry.name.as_str();
 			let name3 = name2.to_uppercase();
 			
 			if set.contains(&name3)
@@ -2329,6 +2572,10 @@ impl Editor
 					{
 						get_editor().rename();
 					}
+					else if &handle == &evt_ui.set_sizes
+					{
+						get_editor().set_sizes();
+					}
 					else if &handle == &evt_ui._move
 					{
 						get_editor()._move();
@@ -0,0 +1,85 @@
+use std::error;
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum ImgError
+{
+	Io(io::Error),
+	TruncatedHeader,
+	DirectoryOutOfRange,
+	BadMagic([u8; 4]),
+	DecryptFailed,
+	EncryptFailed,
+	NameEncoding(String),
+	InvalidName(String),
+	ValueTooLarge(String, u64),
+	EntryOverlap(String),
+	MissingKey(String),
+	Unsupported(String)
+}
+
+impl ImgError
+{
+	// a short read while reading a header
+	pub fn header(error: io::Error) -> ImgError
+	{
+		match error.kind()
+		{
+			io::ErrorKind::UnexpectedEof => ImgError::TruncatedHeader,
+			_ => ImgError::Io(error)
+		}
+	}
+	
+	// a short read while reading a directory
+	pub fn directory(error: io::Error) -> ImgError
+	{
+		match error.kind()
+		{
+			io::ErrorKind::UnexpectedEof => ImgError::DirectoryOutOfRange,
+			_ => ImgError::Io(error)
+		}
+	}
+}
+
+impl fmt::Display for ImgError
+{
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+	{
+		match self
+		{
+			ImgError::Io(error) => write!(f, "{}", error),
+			ImgError::TruncatedHeader => write!(f, "file is too short to contain a header"),
+			ImgError::DirectoryOutOfRange => write!(f, "directory extends past the end of the file"),
+			ImgError::BadMagic(magic) => write!(f, "unknown file format (magic {:02X} {:02X} {:02X} {:02X})", magic[0], magic[1], magic[2], magic[3]),
+			ImgError::DecryptFailed => write!(f, "unable to decrypt the header or directory"),
+			ImgError::EncryptFailed => write!(f, "unable to encrypt the header or directory"),
+			ImgError::NameEncoding(name) => write!(f, "entry name \"{}\" has characters that can't be stored in the archive", name),
+			ImgError::InvalidName(name) => write!(f, "\"{}\" is not a valid entry name", name),
+			ImgError::ValueTooLarge(field_name, value) => write!(f, "{} {} is too large for the archive format", field_name, value),
+			ImgError::EntryOverlap(name) => write!(f, "entry \"{}\" overlaps the directory or another entry", name),
+			ImgError::MissingKey(name) => write!(f, "the archive is encrypted and no \"{}\" key is loaded", name),
+			ImgError::Unsupported(text) => write!(f, "{} is not supported", text)
+		}
+	}
+}
+
+impl error::Error for ImgError
+{
+	fn source(&self) -> Option<&(dyn error::Error + 'static)>
+	{
+		match self
+		{
+			ImgError::Io(error) => Some(error),
+			_ => None
+		}
+	}
+}
+
+impl From<io::Error> for ImgError
+{
+	fn from(error: io::Error) -> Self
+	{
+		ImgError::Io(error)
+	}
+}
@@ -0,0 +1,104 @@
+use super::super::error::ImgError;
+use super::super::keystore::Keystore;
+
+use super::Format;
+use super::FormatType;
+use super::ReadSeek;
+use super::entry::RpfEntry;
+
+use super::img;
+use super::rpf;
+
+// archive format
+pub trait ArchiveFormat
+{
+	// identity
+	fn get_format_type(&self) -> FormatType;
+	fn get_version(&self) -> u8;
+	fn get_name(&self) -> &'static str;
+	
+	fn is_encrypted(&self) -> bool
+	{
+		false
+	}
+	
+	// detect from the first 20 bytes of the file, or less if the file is shorter
+	fn detect(&self, header: &[u8]) -> bool;
+	
+	// detect, for formats that need a key to read their header
+	fn detect_with_keys(&self, header: &[u8], _keys: &Keystore) -> bool
+	{
+		self.detect(header)
+	}
+	
+	// parse/save, reader is the DIR file when needs_dir_file() is true
+	fn parse(&self, format: &mut Format, reader: &mut dyn ReadSeek) -> Result<(), ImgError>;
+	fn save(&self, format: &mut Format, img_path_out: &str, dir_path_out: &str) -> Result<(), ImgError>;
+	
+	// layout
+	fn get_header_size(&self) -> u64;
+	fn get_directory_entry_size(&self) -> u64;
+	
+	fn get_names_size(&self, _format: &Format) -> u64
+	{
+		0
+	}
+	
+	// names in a name table have no limit, the fixed size name fields of IMG versions 1 and 2 override this
+	fn get_max_entry_name_length(&self) -> usize
+	{
+		usize::MAX
+	}
+	
+	// capabilities
+	fn supports_resource_types(&self) -> bool
+	{
+		false
+	}
+	
+	fn supports_folders(&self) -> bool
+	{
+		false
+	}
+	
+	fn needs_dir_file(&self) -> bool
+	{
+		false
+	}
+	
+	// header for resources stored without one, GTA IV's RSC5 layout unless the format overrides it
+	fn get_resource_header(&self, _format: &Format, rpf: &RpfEntry, resource_type: u32) -> Vec<u8>
+	{
+		let mut header = Vec::with_capacity(12);
+		header.extend(b"RSC\x05");
+		header.extend(&resource_type.to_le_bytes());
+		header.extend(&rpf.resource_flags.to_le_bytes());
+		header
+	}
+}
+
+// formats
+pub fn get_archive_formats() -> Vec<Box<dyn ArchiveFormat>>
+{
+	vec![
+		Box::new(img::version1::ImgVersion1),
+		Box::new(img::version2::ImgVersion2),
+		Box::new(img::version3_unencrypted::ImgVersion3Unencrypted),
+		Box::new(rpf::version0::RpfVersion0),
+		Box::new(rpf::version2::RpfVersion2 { encrypted: false }),
+		Box::new(rpf::version2::RpfVersion2 { encrypted: true }),
+		Box::new(rpf::version3::RpfVersion3 { encrypted: false }),
+		Box::new(rpf::version3::RpfVersion3 { encrypted: true }),
+		Box::new(rpf::version4::RpfVersion4),
+		Box::new(rpf::version6::RpfVersion6),
+		Box::new(rpf::version7::RpfVersion7),
+		
+		// checked last, as detecting it needs a decrypt
+		Box::new(img::version3_encrypted::ImgVersion3Encrypted)
+	]
+}
+
+pub fn get_archive_format(format_type: FormatType, version: u8, encrypted: bool) -> Option<Box<dyn ArchiveFormat>>
+{
+	get_archive_formats().into_iter().find(|archive_format| archive_format.get_format_type() == format_type && archive_format.get_version() == version && archive_format.is_encrypted() == encrypted)
+}
@@ -0,0 +1,204 @@
+use std::convert::TryInto;
+use std::io::Read;
+use std::io::Seek;
+use std::io::Write;
+
+use super::super::error::ImgError;
+
+
+
+
+
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct Entry
+{
+	pub index: u32,
+	pub name: String,
+	pub offset_in: u64,
+	pub offset_out: u64,
+	pub size: u64,
+	pub data_temp_path: String,
+	
+	// IMG version 2: the streaming size in the directory entry, in bytes, the same as size unless it is set apart from the data
+	pub streaming_size: u64,
+	
+	// IMG version 2: the second size in the directory entry, in bytes, 0 when unset
+	pub size_in_archive: u64,
+	
+	pub resource_type: u32,
+	
+	// IMG version 3: the item size, the RSC flags of resources, with their memory sizes
+	pub resource_flags: u32,
+	
+	pub flags: u16,
+	
+	pub rpf: Option<RpfEntry>
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Default)]
+pub struct RpfEntry
+{
+	pub folder_path: String,
+	pub full_name: String,
+	pub uncompressed_size: u32,
+	pub is_compressed: bool,
+	pub is_resource: bool,
+	pub resource_flags: u32,
+	pub resource_graphics_flags: u32,
+	
+	// encryption of the entry data, 0 when not encrypted
+	pub encryption: u32
+}
+
+impl Entry
+{
+	// entry data
+	pub fn set_data(&mut self, data_temp_path: String, data: &Vec<u8>) -> Result<(), ImgError>
+	{
+		let mut data = data.as_slice();
+		
+		// new data is stored uncompressed, and RPF resources without their RSC header
+		if let Some(rpf) = self.rpf.as_mut()
+		{
+			if rpf.is_resource && data.len() >= 12 && &data[0..4] == b"RSC\x05"
+			{
+				self.resource_type = u32::from_le_bytes(data[4..8].try_into().unwrap());
+				rpf.resource_flags = u32::from_le_bytes(data[8..12].try_into().unwrap());
+				data = &data[12..];
+			}
+			
+			rpf.uncompressed_size = data.len() as u32;
+			rpf.is_compressed = false;
+			rpf.encryption = 0;
+		}
+		else
+		{
+			let (resource_type, resource_flags) = super::resource::get_resource_info(&self.get_path(), data);
+			self.resource_type = resource_type;
+			self.resource_flags = resource_flags;
+		}
+		
+		super::super::utility::set_file_data(data_temp_path.clone(), &data.to_vec())?;
+		
+		self.data_temp_path = data_temp_path;
+		self.size = super::super::utility::to_sector_bytes(data.len() as u64);
+		self.streaming_size = self.size;
+		
+		// archives that set both sizes keep them matching
+		if self.size_in_archive != 0
+		{
+			self.size_in_archive = self.size;
+		}
+		
+		Ok(())
+	}
+	
+	pub fn get_data_with_reader<R: Read + Seek>(&mut self, reader: &mut R) -> Result<Vec<u8>, ImgError>
+	{
+		if self.data_temp_path.is_empty()
+		{
+			super::super::utility::get_file_data_range_with_reader(reader, self.offset_in, self.size)
+		}
+		else
+		{
+			super::super::utility::get_file_data(self.data_temp_path.clone())
+		}
+	}
+	
+	// copy the entry's data to a writer without loading it into memory, returns the byte count
+	pub fn copy_data_with_reader<R: Read + Seek, W: Write>(&self, reader: &mut R, writer: &mut W) -> Result<u64, ImgError>
+	{
+		if self.data_temp_path.is_empty()
+		{
+			super::super::utility::copy_file_data_range_with_reader(reader, self.offset_in, self.size, writer)
+		}
+		else
+		{
+			super::super::utility::copy_file_data(self.data_temp_path.clone(), writer)
+		}
+	}
+	
+	// size of the entry's data, the data file's size for added or replaced entries
+	pub fn get_data_size(&self) -> u64
+	{
+		if self.data_temp_path.is_empty()
+		{
+			self.size
+		}
+		else
+		{
+			super::super::utility::get_file_size(self.data_temp_path.clone())
+		}
+	}
+	
+	// entry name
+	pub fn set_name(&mut self, new_entry_name: &String)
+	{
+		self.name = new_entry_name.clone();
+		
+		if let Some(rpf) = self.rpf.as_mut()
+		{
+			rpf.full_name = new_entry_name.clone();
+		}
+	}
+	
+	// entry path, including the folder for archives that have folders
+	pub fn get_path(&self) -> String
+	{
+		match &self.rpf
+		{
+			Some(rpf) => format!("{}{}", rpf.folder_path, rpf.full_name),
+			None => self.name.clone()
+		}
+	}
+	
+	// size after decompressing, for archives that compress entries
+	pub fn get_uncompressed_size(&self) -> u64
+	{
+		match &self.rpf
+		{
+			Some(rpf) => rpf.uncompressed_size as u64,
+			None => self.size
+		}
+	}
+	
+	// resource type and memory sizes, for IMG resources, empty for other entries
+	pub fn get_resource_text(&self) -> String
+	{
+		if self.rpf.is_some() || self.resource_type == super::resource::RESOURCE_TYPE_NONE
+		{
+			return String::from("");
+		}
+		
+		format!("type {}, virtual {}, physical {}", self.resource_type, super::resource::get_virtual_size(self.resource_flags), super::resource::get_physical_size(self.resource_flags))
+	}
+	
+	// entry extension
+	pub fn get_extension(&mut self) -> String
+	{
+		let ext = super::super::vendor::get_extension_from_filename(&self.name);
+		match ext
+		{
+			None => String::from(""),
+			_ => ext.unwrap().to_string()
+		}
+	}
+	
+	// entry offset
+	pub fn get_offset_in_sectors(&self) -> u64
+	{
+		super::super::utility::to_sectors(self.offset_in)
+	}
+	
+	pub fn get_offset_out_sectors(&self) -> u64
+	{
+		super::super::utility::to_sectors(self.offset_out)
+	}
+	
+	// entry size
+	pub fn get_size_sectors(&self) -> u64
+	{
+		super::super::utility::to_sectors(self.size)
+	}
+}
\ No newline at end of file
@@ -0,0 +1,216 @@
+use std::path::Component;
+use std::path::PathBuf;
+
+use super::entry::Entry;
+
+// a folder in the tree view of the entries, entries stay in the flat list and folders refer to them by index
+#[derive(Clone, Default)]
+pub struct EntryFolder
+{
+	pub name: String,
+	
+	// path inside the archive, ending in a slash, empty for the root folder
+	pub path: String,
+	
+	pub folders: Vec<EntryFolder>,
+	pub entry_indices: Vec<usize>
+}
+
+impl EntryFolder
+{
+	// folder lookup by path, e.g. "a/b" or "a/b/", not case sensitive
+	pub fn get_folder(&self, path: &str) -> Option<&EntryFolder>
+	{
+		let path2 = get_folder_key(&get_folder_path(path));
+		if path2 == get_folder_key(&self.path)
+		{
+			return Some(self);
+		}
+		
+		for folder in self.folders.iter()
+		{
+			if path2.starts_with(&get_folder_key(&folder.path))
+			{
+				return folder.get_folder(path);
+			}
+		}
+		None
+	}
+	
+	// entries in this folder and all sub folders
+	pub fn get_entry_indices_recursive(&self) -> Vec<usize>
+	{
+		let mut entry_indices = self.entry_indices.clone();
+		for folder in self.folders.iter()
+		{
+			entry_indices.extend(folder.get_entry_indices_recursive());
+		}
+		entry_indices
+	}
+	
+	// folders that only differ in case are the same folder, named as they were first seen
+	fn add_entry(&mut self, entry_index: usize, folder_names: &[&str])
+	{
+		if folder_names.is_empty()
+		{
+			self.entry_indices.push(entry_index);
+			return;
+		}
+		
+		let name = folder_names[0];
+		let key = get_folder_key(name);
+		
+		let folder_index = match self.folders.iter().position(|folder| get_folder_key(&folder.name) == key)
+		{
+			Some(folder_index) => folder_index,
+			None =>
+			{
+				self.folders.push(EntryFolder
+				{
+					name: name.to_string(),
+					path: format!("{}{}/", self.path, name),
+					folders: Vec::new(),
+					entry_indices: Vec::new()
+				});
+				self.folders.len() - 1
+			}
+		};
+		self.folders[folder_index].add_entry(entry_index, &folder_names[1..]);
+	}
+}
+
+// tree view of the entries, archives without folders have every entry in the root folder
+pub fn get_entry_tree(entries: &Vec<Entry>) -> EntryFolder
+{
+	let mut root = EntryFolder::default();
+	for (entry_index, entry) in entries.iter().enumerate()
+	{
+		let (folder_path, _name) = split_path(&entry.get_path());
+		let folder_names : Vec<&str> = folder_path.split('/').filter(|name| !name.is_empty()).collect();
+		root.add_entry(entry_index, &folder_names);
+	}
+	root
+}
+
+// folder path ending in a slash and the name, e.g. ("a/b/", "c.wdr") for "a/b/c.wdr"
+pub fn split_path(path: &str) -> (String, String)
+{
+	match path.rfind('/')
+	{
+		Some(pos) => (path[0..pos+1].to_string(), path[pos+1..].to_string()),
+		None => (String::from(""), path.to_string())
+	}
+}
+
+// folder name or path for comparing, folders are not case sensitive
+pub fn get_folder_key(path: &str) -> String
+{
+	path.to_lowercase()
+}
+
+// entry or folder path with forward slashes and no leading slash
+pub fn get_entry_path(path: &str) -> String
+{
+	path.replace('\\', "/").trim_start_matches('/').to_string()
+}
+
+// folder path with forward slashes, ending in a slash unless it is the root folder
+pub fn get_folder_path(path: &str) -> String
+{
+	let mut path2 = get_entry_path(path);
+	if !path2.is_empty() && !path2.ends_with('/')
+	{
+		path2.push('/');
+	}
+	path2
+}
+
+// entry path as a path relative to an export folder, None when it has a "..", a root or a drive prefix, which would write outside the folder
+pub fn get_export_path(path: &str) -> Option<PathBuf>
+{
+	// a root, or no name after the last slash
+	if path.starts_with('/') || path.starts_with('\\') || path.ends_with('/') || path.ends_with('\\')
+	{
+		return None;
+	}
+	
+	let mut path2 = PathBuf::new();
+	for name in path.split(['/', '\\'])
+	{
+		if name.is_empty() || name == "."
+		{
+			continue;
+		}
+		
+		if name == ".." || name.contains(':')
+		{
+			return None;
+		}
+		path2.push(name);
+	}
+	
+	// checked again with the platform's own path rules
+	if path2.as_os_str().is_empty() || !path2.components().all(|component| matches!(component, Component::Normal(_)))
+	{
+		return None;
+	}
+	Some(path2)
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use super::super::entry::RpfEntry;
+	
+	fn get_rpf_entry(index: u32, path: &str) -> Entry
+	{
+		let (folder_path, name) = split_path(path);
+		Entry
+		{
+			index: index,
+			name: name.clone(),
+			offset_in: 0,
+			offset_out: 0,
+			size: 0,
+			data_temp_path: String::from(""),
+			streaming_size: 0,
+			size_in_archive: 0,
+			resource_type: 0,
+			resource_flags: 0,
+			flags: 0,
+			rpf: Some(RpfEntry
+			{
+				folder_path: folder_path,
+				full_name: name,
+				..Default::default()
+			})
+		}
+	}
+	
+	#[test]
+	fn folders_that_only_differ_in_case_are_one_folder()
+	{
+		let entries = vec![get_rpf_entry(0, "a/B/x.wdr"), get_rpf_entry(1, "a/b/y.wdr"), get_rpf_entry(2, "a/c/z.wdr")];
+		let root = get_entry_tree(&entries);
+		
+		assert_eq!(root.folders.len(), 1);
+		assert_eq!(root.folders[0].folders.len(), 2);
+		assert_eq!(root.get_folder("a/b").unwrap().entry_indices, vec![0, 1]);
+		assert_eq!(root.get_folder("A/B/").unwrap().path, "a/B/");
+		assert_eq!(root.get_folder("a").unwrap().get_entry_indices_recursive(), vec![0, 1, 2]);
+		assert!(root.get_folder("a/d").is_none());
+	}
+	
+	#[test]
+	fn export_paths_stay_inside_the_folder()
+	{
+		assert_eq!(get_export_path("a/b/c.wdr"), Some(PathBuf::from("a").join("b").join("c.wdr")));
+		assert_eq!(get_export_path("a\\b.wdr"), Some(PathBuf::from("a").join("b.wdr")));
+		assert_eq!(get_export_path("a/../../b.wdr"), None);
+		assert_eq!(get_export_path("/etc/passwd"), None);
+		assert_eq!(get_export_path("\\\\server\\share\\b.wdr"), None);
+		assert_eq!(get_export_path("C:/b.wdr"), None);
+		assert_eq!(get_export_path("a/"), None);
+	}
+}
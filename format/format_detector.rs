@@ -0,0 +1,72 @@
+use std::fs;
+use std::fs::File;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+
+use super::super::error::ImgError;
+use super::super::keystore::Keystore;
+
+use super::archive_format;
+use super::archive_format::ArchiveFormat;
+
+// detect
+pub fn detect_version(img_path_in: &String, keys: &Keystore) -> Result<Box<dyn ArchiveFormat>, ImgError>
+{
+	let mut file1 = File::open(img_path_in)?;
+	
+	let result = detect_version_from(&mut file1, keys);
+	match result
+	{
+		Err(ImgError::TruncatedHeader) | Err(ImgError::BadMagic(_)) =>
+		{
+			// version 1, the IMG file has no header
+			let metadata = fs::metadata(super::super::utility::replace_file_extension(img_path_in.as_str(), "dir").unwrap());
+			match metadata
+			{
+				Err(_) => {},
+				_ =>
+				{
+					if metadata.unwrap().is_file()
+					{
+						return Ok(Box::new(super::img::version1::ImgVersion1));
+					}
+				}
+			}
+			result
+		},
+		_ => result
+	}
+}
+
+// detect, version 1 is not detected as it needs the DIR file
+pub fn detect_version_from<R: Read + Seek>(reader: &mut R, keys: &Keystore) -> Result<Box<dyn ArchiveFormat>, ImgError>
+{
+	let mut buffer2 = Vec::with_capacity(20);
+	reader.seek(SeekFrom::Start(0))?;
+	reader.take(20).read_to_end(&mut buffer2)?;
+	
+	for archive_format in archive_format::get_archive_formats()
+	{
+		if archive_format.detect_with_keys(&buffer2, keys)
+		{
+			return Ok(archive_format);
+		}
+	}
+	
+	if buffer2.len() < 16
+	{
+		return Err(ImgError::TruncatedHeader);
+	}
+	
+	// RPF versions without a parser yet
+	if &buffer2[0..3] == b"RPF"
+	{
+		return Err(ImgError::Unsupported(format!("RPF version {}", buffer2[3] as char)));
+	}
+	
+	// unknown version
+	let mut buffer1 = [0; 4];
+	buffer1.copy_from_slice(&buffer2[0..4]);
+	Err(ImgError::BadMagic(buffer1))
+}
@@ -0,0 +1,75 @@
+pub mod version1;
+pub mod version2;
+pub mod version3_encrypted;
+pub mod version3_unencrypted;
+
+use crate::error::ImgError;
+
+use super::entry::Entry;
+
+// IMG versions 1 and 2 store each name in a fixed size field
+pub const NAME_FIELD_SIZE : usize = 24;
+
+// every name fits a fixed size name field, checked before saving so a long name doesn't leave a partly written file
+pub fn check_names(format: &super::Format) -> Result<(), ImgError>
+{
+	for entry in format.entries.iter()
+	{
+		get_name_bytes(entry)?;
+	}
+	Ok(())
+}
+
+// every entry's data starts after the directory and after the end of the entry before it, checked before saving as entries are written at their offset
+pub fn check_entry_offsets(format: &mut super::Format, directory_end: u64) -> Result<(), ImgError>
+{
+	let mut end_offset = directory_end;
+	for entry in format.get_entries_sorted_by_offset_out().iter()
+	{
+		if entry.offset_out < end_offset
+		{
+			return Err(ImgError::EntryOverlap(entry.get_path()));
+		}
+		end_offset = entry.offset_out + crate::utility::to_sector_bytes(entry.get_data_size());
+	}
+	Ok(())
+}
+
+// name for a fixed size name field, padded with zeros
+pub fn get_name_bytes(entry: &Entry) -> Result<[u8; NAME_FIELD_SIZE], ImgError>
+{
+	let name_bytes = crate::name_codec::encode(&entry.name)?;
+	if name_bytes.len() > NAME_FIELD_SIZE
+	{
+		return Err(ImgError::Unsupported(format!("entry name \"{}\" of more than {} characters in IMG version 1 or 2", entry.name, NAME_FIELD_SIZE)));
+	}
+	
+	let mut name = [0; NAME_FIELD_SIZE];
+	name[0..name_bytes.len()].copy_from_slice(&name_bytes);
+	Ok(name)
+}
+
+// IMG version 3 item size, the RSC flags of resources and the byte size of other entries
+// entries read from the archive keep their item size, entries without a resource type that were added, replaced or read from another version get their byte size
+pub fn get_version3_item_size(entry: &Entry) -> Result<u32, ImgError>
+{
+	if entry.resource_type != super::resource::RESOURCE_TYPE_NONE || (entry.data_temp_path.is_empty() && entry.resource_flags != 0)
+	{
+		return Ok(entry.resource_flags);
+	}
+	crate::utility::to_u32_field(entry.get_data_size(), "entry size")
+}
+
+// IMG version 3 directory flags, the low 11 bits are the unused bytes in the entry's last sector
+// entries read from the archive keep their flags as they are, added or replaced entries get the padding of their data file
+pub fn get_version3_flags(entry: &Entry) -> u16
+{
+	if entry.data_temp_path.is_empty()
+	{
+		return entry.flags;
+	}
+	
+	let remainder = entry.get_data_size() % 2048;
+	let padding = if remainder == 0 { 0 } else { 2048 - remainder };
+	(entry.flags & 0xF800) | (padding as u16)
+}
@@ -0,0 +1,245 @@
+use std::convert::TryInto;
+use std::fs::File;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Read;
+use std::io::Write;
+
+use crate::error::ImgError;
+
+use super::super::Format;
+use super::super::FormatType;
+use super::super::ReadSeek;
+use super::super::archive_format::ArchiveFormat;
+
+// format
+pub struct ImgVersion1;
+
+impl ArchiveFormat for ImgVersion1
+{
+	fn get_format_type(&self) -> FormatType
+	{
+		FormatType::IMG
+	}
+	
+	fn get_version(&self) -> u8
+	{
+		1
+	}
+	
+	fn get_name(&self) -> &'static str
+	{
+		"IMG version 1"
+	}
+	
+	fn detect(&self, _header: &[u8]) -> bool
+	{
+		// no header, detected by the DIR file existing
+		false
+	}
+	
+	fn parse(&self, format: &mut Format, mut reader: &mut dyn ReadSeek) -> Result<(), ImgError>
+	{
+		parse_list(format, &mut reader)
+	}
+	
+	fn save(&self, format: &mut Format, img_path_out: &str, dir_path_out: &str) -> Result<(), ImgError>
+	{
+		save_list(format, img_path_out, dir_path_out)
+	}
+	
+	fn get_header_size(&self) -> u64
+	{
+		0
+	}
+	
+	fn get_directory_entry_size(&self) -> u64
+	{
+		32
+	}
+	
+	fn get_max_entry_name_length(&self) -> usize
+	{
+		super::NAME_FIELD_SIZE
+	}
+	
+	fn needs_dir_file(&self) -> bool
+	{
+		true
+	}
+}
+
+// parse
+pub fn parse_list<R: Read>(format: &mut super::super::Format, dir_reader: &mut R) -> Result<(), ImgError>
+{
+	let mut buffer = Vec::new();
+	dir_reader.read_to_end(&mut buffer)?;
+	
+	let entry_count = (buffer.len() as u64) / 32;
+	
+	format.entries = Vec::with_capacity(entry_count as usize);
+	
+	for i in 0..entry_count
+	{
+		format.entries.push(parse_entry(&mut buffer, i));
+	}
+	
+	Ok(())
+}
+
+pub fn parse_entry(buffer: &mut Vec<u8>, i: u64) -> super::super::entry::Entry
+{
+	let seek = (i * 32) as usize;
+	let offset = crate::utility::sectors_to_bytes(u32::from_le_bytes(buffer[seek..seek+4].try_into().unwrap()) as u64);
+	let size = crate::utility::sectors_to_bytes(u32::from_le_bytes(buffer[seek+4..seek+8].try_into().unwrap()) as u64);
+	
+	super::super::entry::Entry
+	{
+		index: i as u32,
+		offset_in: offset,
+		offset_out: offset,
+		size: size,
+		name: crate::name_codec::decode(&buffer[seek+8..seek+32]),
+		data_temp_path: String::from(""),
+		streaming_size: size,
+		size_in_archive: 0,
+		resource_type: 0,
+		resource_flags: 0,
+		flags: 0,
+		rpf: None
+	}
+}
+
+// save
+pub fn save_list(format: &mut super::super::Format, img_path_out: &str, dir_path_out: &str) -> Result<(), ImgError>
+{
+	super::check_names(format)?;
+	super::check_entry_offsets(format, 0)?;
+	
+	// IMG file
+	{
+		let img_uses_temp_path_out : bool = format.img_path_in == img_path_out;
+		
+		let img_temp_path_out : String = crate::utility::get_next_file_path2(format.get_saving_dir()?, "temp.img".to_string());
+		let img_path_out2 = if img_uses_temp_path_out
+		{
+			img_temp_path_out
+		}
+		else
+		{
+			img_path_out.to_string()
+		};
+		
+		let file_out = File::create(&img_path_out2)?;
+		let mut buffer_out = BufWriter::new(file_out);
+		
+		let mut seek : u64 = 0;
+		
+		let img_is_new = format.is_new();
+		
+		let mut reader : Option<BufReader<File>> = None;
+		
+		if !img_is_new
+		{
+			reader = Some(BufReader::new(File::open(&format.img_path_in)?));
+		}
+		
+		let entries : Vec<super::super::entry::Entry> = format.get_entries_sorted_by_offset_out();
+		for entry in entries
+		{
+			// pad entry gaps
+			let entry_offset = entry.offset_out;
+			if seek < entry_offset
+			{
+				let diff = entry_offset - seek;
+				crate::utility::write_zeros_to(&mut buffer_out, diff)?;
+				seek += diff;
+			}
+			
+			// copy entry data
+			let data_size = if img_is_new
+			{
+				format.copy_entry_data(&entry, &mut buffer_out)?
+			}
+			else
+			{
+				entry.copy_data_with_reader(reader.as_mut().unwrap(), &mut buffer_out)?
+			};
+			seek += data_size;
+			
+			// pad entry data
+			if seek % 2048 != 0
+			{
+				let remainder = 2048 - (seek % 2048);
+				crate::utility::write_zeros_to(&mut buffer_out, remainder)?;
+				seek += remainder;
+			}
+		}
+		
+		buffer_out.flush()?;
+		drop(buffer_out);
+		
+		if img_uses_temp_path_out
+		{
+			crate::utility::move_file_over(&img_path_out2, img_path_out)?;
+		}
+		
+		//let img_temp_path_out : String = crate::utility::get_next_file_path2(format.get_saving_dir()?, "temp.dir".to_string());
+		//crate::utility::set_file_data_overlap(img_path_out.to_string(), &buffer, img_uses_temp_path_out, img_temp_path_out.to_string());
+	}
+	
+	// DIR file
+	{
+		let dir_uses_temp_path_out : bool = format.dir_path_in == dir_path_out;
+		
+		let dir_temp_path_out : String = crate::utility::get_next_file_path2(format.get_saving_dir()?, "temp.dir".to_string());
+		let dir_path_out2 = if dir_uses_temp_path_out
+		{
+			dir_temp_path_out
+		}
+		else
+		{
+			dir_path_out.to_string()
+		};
+		
+		let file_out = File::create(&dir_path_out2)?;
+		let mut buffer_out = BufWriter::new(file_out);
+		
+		
+		let mut buffer = vec![0; 32];
+		
+		for entry in format.entries.iter()
+		{
+			let bytes1 : [u8; 4] = crate::utility::to_u32_field(entry.get_offset_out_sectors(), "entry offset in sectors")?.to_le_bytes();
+			let bytes2 : [u8; 4] = crate::utility::to_u32_field(entry.get_size_sectors(), "entry size in sectors")?.to_le_bytes();
+			
+			buffer[0] = bytes1[0];
+			buffer[1] = bytes1[1];
+			buffer[2] = bytes1[2];
+			buffer[3] = bytes1[3];
+			
+			buffer[4] = bytes2[0];
+			buffer[5] = bytes2[1];
+			buffer[6] = bytes2[2];
+			buffer[7] = bytes2[3];
+			
+			let name = super::get_name_bytes(entry)?;
+			buffer[8..32].copy_from_slice(&name);
+			
+			buffer_out.write_all(buffer.as_slice())?;
+		}
+		
+		buffer_out.flush()?;
+		drop(buffer_out);
+		
+		if dir_uses_temp_path_out
+		{
+			crate::utility::move_file_over(&dir_path_out2, dir_path_out)?;
+		}
+		
+		//let dir_temp_path_out : String = crate::utility::get_next_file_path2(format.get_saving_dir()?, "temp.dir".to_string());
+		//crate::utility::set_file_data_overlap(dir_path_out.to_string(), &buffer, dir_uses_temp_path_out, dir_temp_path_out.to_string());
+	}
+	
+	Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,262 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Read;
+use std::io::Write;
+use std::io::Seek;
+use std::io::SeekFrom;
+
+use crate::error::ImgError;
+
+use super::super::Format;
+use super::super::FormatType;
+use super::super::ReadSeek;
+use super::super::archive_format::ArchiveFormat;
+
+// format
+pub struct ImgVersion2;
+
+impl ArchiveFormat for ImgVersion2
+{
+	fn get_format_type(&self) -> FormatType
+	{
+		FormatType::IMG
+	}
+	
+	fn get_version(&self) -> u8
+	{
+		2
+	}
+	
+	fn get_name(&self) -> &'static str
+	{
+		"IMG version 2"
+	}
+	
+	fn detect(&self, header: &[u8]) -> bool
+	{
+		header.len() >= 4 && &header[0..4] == b"VER2"
+	}
+	
+	fn parse(&self, format: &mut Format, mut reader: &mut dyn ReadSeek) -> Result<(), ImgError>
+	{
+		parse_list(format, &mut reader)
+	}
+	
+	fn save(&self, format: &mut Format, img_path_out: &str, _dir_path_out: &str) -> Result<(), ImgError>
+	{
+		save_list(format, img_path_out)
+	}
+	
+	fn get_header_size(&self) -> u64
+	{
+		8
+	}
+	
+	fn get_directory_entry_size(&self) -> u64
+	{
+		32
+	}
+	
+	fn get_max_entry_name_length(&self) -> usize
+	{
+		super::NAME_FIELD_SIZE
+	}
+}
+
+// parse
+pub fn parse_list<R: Read + Seek>(format: &mut super::super::Format, reader: &mut R) -> Result<(), ImgError>
+{
+	let file_size = crate::utility::get_reader_size(reader)?;
+	reader.seek(SeekFrom::Start(0))?;
+	
+	let mut buffer = [0; 8];
+	reader.read_exact(&mut buffer).map_err(ImgError::header)?;
+	
+	let buf1 = [buffer[0], buffer[1], buffer[2], buffer[3]];
+	let buf2 = [buffer[4], buffer[5], buffer[6], buffer[7]];
+	let _file_stamp = u32::from_le_bytes(buf1);
+	let entry_count = u32::from_le_bytes(buf2);
+	
+	crate::utility::check_directory_range(file_size, 8, entry_count as u64, 32)?;
+	
+	format.entries = Vec::with_capacity(entry_count as usize);
+	
+	for i in 0..entry_count
+	{
+		format.entries.push(parse_entry(reader, i as u64)?);
+	}
+	
+	Ok(())
+}
+
+pub fn parse_entry<R: Read + Seek>(reader: &mut R, i: u64) -> Result<super::super::entry::Entry, ImgError>
+{
+	let seek = (8 + (i * 32)) as usize;
+	reader.seek(SeekFrom::Start(seek as u64))?;
+	
+	let mut buffer = [0; 32];
+	reader.read_exact(&mut buffer).map_err(ImgError::directory)?;
+	
+	let buf1 = [buffer[0], buffer[1], buffer[2], buffer[3]];
+	let buf2 = [buffer[4], buffer[5]];
+	let buf3 = [buffer[6], buffer[7]];
+	
+	let offset = crate::utility::sectors_to_bytes(u32::from_le_bytes(buf1) as u64);
+	let size = crate::utility::sectors_to_bytes(u16::from_le_bytes(buf2) as u64);
+	
+	Ok(super::super::entry::Entry
+	{
+		index: i as u32,
+		offset_in: offset,
+		offset_out: offset,
+		size: size,
+		name: crate::name_codec::decode(&buffer[8..32]),
+		data_temp_path: String::from(""),
+		streaming_size: size,
+		size_in_archive: crate::utility::sectors_to_bytes(u16::from_le_bytes(buf3) as u64),
+		resource_type: 0,
+		resource_flags: 0,
+		flags: 0,
+		rpf: None
+	})
+}
+
+// save
+pub fn save_list(format: &mut super::super::Format, img_path_out: &str) -> Result<(), ImgError>
+{
+	super::check_names(format)?;
+	super::check_entry_offsets(format, 8 + (format.entries.len() as u64) * 32)?;
+	
+	// IMG file
+	{
+		let img_uses_temp_path_out : bool = format.img_path_in == img_path_out;
+		
+		let count = format.entries.len();
+		
+		let mut seek : u64 = 0;
+		
+		let img_temp_path_out : String = crate::utility::get_next_file_path2(format.get_saving_dir()?, "temp.img".to_string());
+		let img_path_out2 = if img_uses_temp_path_out
+		{
+			img_temp_path_out
+		}
+		else
+		{
+			img_path_out.to_string()
+		};
+		
+		let file_out = File::create(&img_path_out2)?;
+		let mut buffer_out = BufWriter::new(file_out);
+		
+		let mut buffer = Vec::new();
+		
+		// header
+		let count2 = crate::utility::to_u32_field(count as u64, "entry count")?.to_le_bytes();
+		buffer.push(b'V');
+		buffer.push(b'E');
+		buffer.push(b'R');
+		buffer.push(b'2');
+		buffer.push(count2[0]);
+		buffer.push(count2[1]);
+		buffer.push(count2[2]);
+		buffer.push(count2[3]);
+		seek += 8;
+		buffer_out.write_all(buffer.as_slice())?;
+		
+		// directory
+		buffer.resize(32, 0);
+		
+		for i in 0..count
+		{
+			let entry = format.get_entry_by_index(i as u64).unwrap();
+			
+			let bytes1 : [u8; 4] = crate::utility::to_u32_field(entry.get_offset_out_sectors(), "entry offset in sectors")?.to_le_bytes();
+			let bytes2 : [u8; 2] = crate::utility::to_u16_field(crate::utility::to_sectors(entry.streaming_size), "entry streaming size in sectors")?.to_le_bytes();
+			let bytes3 : [u8; 2] = crate::utility::to_u16_field(crate::utility::to_sectors(entry.size_in_archive), "entry size in archive in sectors")?.to_le_bytes();
+			
+			buffer[0] = bytes1[0];
+			buffer[1] = bytes1[1];
+			buffer[2] = bytes1[2];
+			buffer[3] = bytes1[3];
+			
+			buffer[4] = bytes2[0];
+			buffer[5] = bytes2[1];
+			
+			buffer[6] = bytes3[0];
+			buffer[7] = bytes3[1];
+			
+			let name = super::get_name_bytes(entry)?;
+			buffer[8..32].copy_from_slice(&name);
+			
+			seek += 32;
+			buffer_out.write_all(buffer.as_slice())?;
+		}
+		
+		// pad directory
+		buffer.clear();
+		let remainder2 = 8 + (count * 32);
+		if remainder2 % 2048 != 0
+		{
+			let remainder = 2048 - (remainder2 % 2048);
+			crate::utility::write_zeros(&mut buffer, remainder as u64);
+			seek += remainder as u64;
+			buffer_out.write_all(buffer.as_slice())?;
+		}
+		
+		// entry data
+		let img_is_new = format.is_new();
+		
+		let mut reader : Option<BufReader<File>> = None;
+		
+		if !img_is_new
+		{
+			reader = Some(BufReader::new(File::open(&format.img_path_in)?));
+		}
+		
+		let entries : Vec<super::super::entry::Entry> = format.get_entries_sorted_by_offset_out();
+		for entry in entries
+		{
+			// pad entry gaps
+			let entry_offset = entry.offset_out;
+			if seek < entry_offset
+			{
+				let diff = entry_offset - seek;
+				crate::utility::write_zeros_to(&mut buffer_out, diff)?;
+				seek += diff;
+			}
+			
+			// copy entry data
+			let data_size = if img_is_new
+			{
+				format.copy_entry_data(&entry, &mut buffer_out)?
+			}
+			else
+			{
+				entry.copy_data_with_reader(reader.as_mut().unwrap(), &mut buffer_out)?
+			};
+			seek += data_size;
+			
+			// pad entry data
+			if seek % 2048 != 0
+			{
+				let remainder = 2048 - (seek % 2048);
+				crate::utility::write_zeros_to(&mut buffer_out, remainder)?;
+				seek += remainder;
+			}
+		}
+		
+		buffer_out.flush()?;
+		drop(buffer_out);
+		
+		if img_uses_temp_path_out
+		{
+			crate::utility::move_file_over(&img_path_out2, img_path_out)?;
+		}
+		
+		//let img_temp_path_out : String = crate::utility::get_next_file_path2(format.get_saving_dir()?, "temp.dir".to_string());
+		//crate::utility::set_file_data_overlap(img_path_out.to_string(), &buffer, img_uses_temp_path_out, img_temp_path_out.to_string());
+	}
+	
+	Ok(())
+}
\ No newline at end of file
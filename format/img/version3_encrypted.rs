@@ -0,0 +1,381 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Read;
+use std::io::Write;
+use std::io::Seek;
+use std::io::SeekFrom;
+
+use crate::error::ImgError;
+use crate::keystore;
+use crate::keystore::Keystore;
+
+use super::super::Format;
+use super::super::FormatType;
+use super::super::ReadSeek;
+use super::super::archive_format::ArchiveFormat;
+
+// format
+pub struct ImgVersion3Encrypted;
+
+impl ArchiveFormat for ImgVersion3Encrypted
+{
+	fn get_format_type(&self) -> FormatType
+	{
+		FormatType::IMG
+	}
+	
+	fn get_version(&self) -> u8
+	{
+		3
+	}
+	
+	fn get_name(&self) -> &'static str
+	{
+		"IMG version 3 (encrypted)"
+	}
+	
+	fn is_encrypted(&self) -> bool
+	{
+		true
+	}
+	
+	fn detect(&self, header: &[u8]) -> bool
+	{
+		self.detect_with_keys(header, &Keystore::default())
+	}
+	
+	fn detect_with_keys(&self, header: &[u8], keys: &Keystore) -> bool
+	{
+		if header.len() < 20
+		{
+			return false;
+		}
+		
+		// without the key, the unencrypted table item size after the 16 encrypted bytes tells the format, and parsing asks for the key
+		let key = match keys.get(keystore::KEY_NAME_GTA_4)
+		{
+			Some(key) => key,
+			None => return header[16..18] == u16::to_le_bytes(16)
+		};
+		
+		let mut buffer = header[0..16].to_vec();
+		match crate::utility::decrypt_gta_4(key, &mut buffer)
+		{
+			Ok(_) => buffer[0..4] == u32::to_le_bytes(0xA94E2A52),
+			Err(_) => false
+		}
+	}
+	
+	fn parse(&self, format: &mut Format, mut reader: &mut dyn ReadSeek) -> Result<(), ImgError>
+	{
+		parse_list(format, &mut reader)
+	}
+	
+	fn save(&self, format: &mut Format, img_path_out: &str, _dir_path_out: &str) -> Result<(), ImgError>
+	{
+		save_list(format, img_path_out)
+	}
+	
+	fn get_header_size(&self) -> u64
+	{
+		20
+	}
+	
+	fn get_directory_entry_size(&self) -> u64
+	{
+		16
+	}
+	
+	fn get_names_size(&self, format: &Format) -> u64
+	{
+		format.get_names_len_for_v3()
+	}
+	
+	fn supports_resource_types(&self) -> bool
+	{
+		true
+	}
+}
+
+// parse
+pub fn parse_list<R: Read + Seek>(format: &mut super::super::Format, reader: &mut R) -> Result<(), ImgError>
+{
+	let key = format.keys.get_key(keystore::KEY_NAME_GTA_4)?.clone();
+	
+	let file_size = crate::utility::get_reader_size(reader)?;
+	reader.seek(SeekFrom::Start(0))?;
+	let mut reader = BufReader::new(reader);
+	
+	// read header
+	let mut buffer = vec![0; 20];
+	reader.read_exact(&mut buffer).map_err(ImgError::header)?;
+	
+	// decrypt header, only the first 16 bytes are encrypted
+	let mut buffer_decrypted = buffer.clone();
+	crate::utility::decrypt_gta_4(&key, &mut buffer_decrypted[0..16])?;
+	
+	if buffer_decrypted[0..4] != u32::to_le_bytes(0xA94E2A52)
+	{
+		return Err(ImgError::DecryptFailed);
+	}
+	
+	// parse header
+	let buf1 = [buffer_decrypted[0], buffer_decrypted[1], buffer_decrypted[2], buffer_decrypted[3]];
+	let buf2 = [buffer_decrypted[4], buffer_decrypted[5], buffer_decrypted[6], buffer_decrypted[7]];
+	let buf3 = [buffer_decrypted[8], buffer_decrypted[9], buffer_decrypted[10], buffer_decrypted[11]];
+	let buf4 = [buffer_decrypted[12], buffer_decrypted[13], buffer_decrypted[14], buffer_decrypted[15]];
+	let buf5 = [buffer_decrypted[16], buffer_decrypted[17]];
+	let buf6 = [buffer_decrypted[18], buffer_decrypted[19]];
+	
+	let _file_stamp = u32::from_le_bytes(buf1);
+	let _file_version = u32::from_le_bytes(buf2);
+	let entry_count = u32::from_le_bytes(buf3);
+	let table_data_size = u32::from_le_bytes(buf4);
+	let _table_item_data_size = u16::from_le_bytes(buf5);
+	let _unknown1 = u16::from_le_bytes(buf6);
+	
+	// decrypt directory
+	if (table_data_size as u64) < (entry_count as u64) * 16
+	{
+		return Err(ImgError::DirectoryOutOfRange);
+	}
+	crate::utility::check_directory_range(file_size, 20, table_data_size as u64, 1)?;
+	
+	// a remainder smaller than a block is not encrypted
+	reader.seek(SeekFrom::Start(20))?;
+	let mut buffer_decrypted2 = vec![0; table_data_size as usize];
+	reader.read_exact(&mut buffer_decrypted2).map_err(ImgError::directory)?;
+	crate::utility::decrypt_gta_4(&key, &mut buffer_decrypted2)?;
+	
+	// parse directory I - entry offset, entry size
+	//let table_entry_count = table_data_size / (table_item_data_size as u32);
+	let mut seek = 0;
+	
+	format.entries = Vec::with_capacity(entry_count as usize);
+	
+	//buffer.resize(16 as usize, 0);
+	for i in 0..entry_count
+	{
+		//reader.read_exact(&mut buffer);
+		
+		let buf1 = [buffer_decrypted2[seek], buffer_decrypted2[seek+1], buffer_decrypted2[seek+2], buffer_decrypted2[seek+3]];
+		let buf2 = [buffer_decrypted2[seek+4], buffer_decrypted2[seek+5], buffer_decrypted2[seek+6], buffer_decrypted2[seek+7]];
+		let buf3 = [buffer_decrypted2[seek+8], buffer_decrypted2[seek+9], buffer_decrypted2[seek+10], buffer_decrypted2[seek+11]];
+		let buf4 = [buffer_decrypted2[seek+12], buffer_decrypted2[seek+13]];
+		let buf5 = [buffer_decrypted2[seek+14], buffer_decrypted2[seek+15]];
+		
+		let item_size = u32::from_le_bytes(buf1);
+		let resource_type = u32::from_le_bytes(buf2);
+		let offset = u32::from_le_bytes(buf3);
+		let size = u16::from_le_bytes(buf4);
+		let flags = u16::from_le_bytes(buf5);
+		
+		let offset2 = crate::utility::sectors_to_bytes(offset as u64);
+		let size2 = crate::utility::sectors_to_bytes(size as u64);
+		
+		let entry = super::super::entry::Entry
+		{
+			index: i,
+			offset_in: offset2,
+			offset_out: offset2,
+			size: size2,
+			name: String::from(""),
+			data_temp_path: String::from(""),
+			streaming_size: size2,
+			size_in_archive: 0,
+			resource_type: resource_type,
+			resource_flags: item_size,
+			flags: flags,
+			rpf: None
+		};
+		format.entries.push(entry);
+		
+		seek += 16;
+	}
+	
+	// parse directory II - entry names
+	//buffer.resize(4096, 0);
+	//let mut a = 0;
+	for i in 0..entry_count
+	{
+		//let read = reader.read_until(0, &mut buffer).unwrap();
+		
+		//format.entries[i as usize].name = crate::vendor::clone_into_array(&buffer[0..read]);
+		
+		let entry_name = crate::utility::get_null_string(&mut buffer_decrypted2, seek as u64)?;
+		
+		seek += crate::name_codec::get_encoded_len(&entry_name) + 1;
+		
+		format.entries[i as usize].name = entry_name;
+		
+		
+		
+		//a += entry_name_len as usize;
+	}
+	//a += entry_count as usize;
+	
+	Ok(())
+}
+
+// save
+pub fn save_list(format: &mut super::super::Format, img_path_out: &str) -> Result<(), ImgError>
+{
+	super::check_entry_offsets(format, 20 + (format.entries.len() as u64) * 16 + format.get_names_len_for_v3())?;
+	
+	let img_uses_temp_path_out : bool = format.img_path_in == img_path_out;
+	let key = format.keys.get_key(keystore::KEY_NAME_GTA_4)?.clone();
+	
+	let mut seek : u64 = 0;
+	
+	let img_temp_path_out : String = crate::utility::get_next_file_path2(format.get_saving_dir()?, "temp.img".to_string());
+	let img_path_out2 = if img_uses_temp_path_out
+	{
+		img_temp_path_out
+	}
+	else
+	{
+		img_path_out.to_string()
+	};
+	
+	let file_out = File::create(&img_path_out2)?;
+	let mut buffer_out = BufWriter::new(file_out);
+	
+	let mut unencrypted_buffer : Vec<u8> = Vec::new();
+	let mut buffer : Vec<u8> = Vec::new();
+	let mut names_buffer : Vec<u8> = Vec::new();
+	
+	// header
+	let names_len_v3 = format.get_names_len_for_v3();
+	let entry_count = format.entries.len() as u64;
+	//let table_data_size = crate::utility::to_sector_bytes(((16 * entry_count) + (names_len_v3 as usize)) as u64);
+	let table_data_size = (16 * entry_count) + names_len_v3;
+	let body_start = 20 + (16 * entry_count) + names_len_v3;
+	
+	buffer.extend(&0xA94E2A52u32.to_le_bytes());
+	buffer.extend(&3u32.to_le_bytes());
+	buffer.extend(&crate::utility::to_u32_field(entry_count, "entry count")?.to_le_bytes());
+	buffer.extend(&crate::utility::to_u32_field(table_data_size, "table data size")?.to_le_bytes());
+	buffer.extend(&16u16.to_le_bytes());
+	buffer.extend(&0u16.to_le_bytes());
+	
+	seek += 20;
+	unencrypted_buffer.extend(&buffer);
+	
+	// directory - most entry info
+	for i in 0..entry_count
+	{
+		buffer.clear();
+		
+		let entry = format.get_entry_by_index(i).unwrap();
+		
+		buffer.extend(&super::get_version3_item_size(entry)?.to_le_bytes());
+		buffer.extend(&entry.resource_type.to_le_bytes());
+		buffer.extend(&crate::utility::to_u32_field(entry.get_offset_out_sectors(), "entry offset in sectors")?.to_le_bytes());
+		buffer.extend(&crate::utility::to_u16_field(entry.get_size_sectors(), "entry size in sectors")?.to_le_bytes());
+		
+		buffer.extend(&super::get_version3_flags(entry).to_le_bytes());
+		
+		seek += 16;
+		unencrypted_buffer.extend(&buffer);
+		
+		names_buffer.extend(crate::name_codec::encode(&entry.name)?);
+		names_buffer.push(0);
+	}
+	
+	// directory - entry names
+	unencrypted_buffer.extend(&names_buffer);
+	seek += names_buffer.len() as u64;
+	names_buffer.clear();
+	
+	// padding after directory
+	buffer.clear();
+	
+	// encrypt header and directory
+	/*
+	{
+		let mut encrypted_buffer1 = Vec::new();
+		let mut encrypted_buffer2 = Vec::new();
+		
+		let mut unencrypted_header = (&unencrypted_buffer[0..32]).to_vec();
+		crate::utility::encrypt_gta_4(&mut unencrypted_header, &mut encrypted_buffer1);
+		let encrypted_header = &encrypted_buffer1[0..20];
+		
+		let mut unencrypted_directory = (&unencrypted_buffer[20..(unencrypted_buffer.len())]).to_vec();
+		let pad_len = unencrypted_directory.len();
+		crate::utility::write_zeros(&mut unencrypted_directory, pad_len as u64);
+		crate::utility::encrypt_gta_4(&mut unencrypted_directory, &mut encrypted_buffer2);
+		let encrypted_directory = &encrypted_buffer2[0..(unencrypted_buffer.len() - 20)];
+		
+		buffer_out.write_all(encrypted_header);
+		buffer_out.write_all(encrypted_directory);
+	}
+	*/
+	
+	// in place, the 4 bytes after the first 16 and a directory remainder smaller than a block are not encrypted
+	{
+		crate::utility::encrypt_gta_4(&key, &mut unencrypted_buffer[0..16])?;
+		crate::utility::encrypt_gta_4(&key, &mut unencrypted_buffer[20..])?;
+		buffer_out.write_all(&unencrypted_buffer)?;
+		
+		if body_start % 2048 != 0 && entry_count > 0
+		{
+			let pad_data_size = 2048 - (body_start % 2048);
+			crate::utility::write_zeros(&mut buffer, pad_data_size);
+			seek += pad_data_size;
+			buffer_out.write_all(&buffer)?;
+		}
+	}
+	
+	// entry data
+	let img_is_new = format.is_new();
+	
+	let mut reader : Option<BufReader<File>> = None;
+	
+	if !img_is_new
+	{
+		reader = Some(BufReader::new(File::open(&format.img_path_in)?));
+	}
+	
+	let entries : Vec<super::super::entry::Entry> = format.get_entries_sorted_by_offset_out();
+	for entry in entries
+	{
+		// pad entry gaps
+		let entry_offset = entry.offset_out;
+		if seek < entry_offset
+		{
+			let diff = entry_offset - seek;
+			crate::utility::write_zeros_to(&mut buffer_out, diff)?;
+			seek += diff;
+		}
+		
+		// copy entry data
+		let data_size = if img_is_new
+		{
+			format.copy_entry_data(&entry, &mut buffer_out)?
+		}
+		else
+		{
+			entry.copy_data_with_reader(reader.as_mut().unwrap(), &mut buffer_out)?
+		};
+		seek += data_size;
+		
+		// pad entry data
+		if seek % 2048 != 0
+		{
+			let remainder = 2048 - (seek % 2048);
+			crate::utility::write_zeros_to(&mut buffer_out, remainder)?;
+			seek += remainder;
+		}
+	}
+	
+	buffer_out.flush()?;
+	drop(buffer_out);
+	
+	if img_uses_temp_path_out
+	{
+		crate::utility::move_file_over(&img_path_out2, img_path_out)?;
+	}
+	
+	Ok(())
+}
\ No newline at end of file
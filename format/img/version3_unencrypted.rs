@@ -0,0 +1,295 @@
+use std::fs::File;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Read;
+use std::io::Write;
+use std::io::Seek;
+use std::io::SeekFrom;
+
+use crate::error::ImgError;
+
+use super::super::Format;
+use super::super::FormatType;
+use super::super::ReadSeek;
+use super::super::archive_format::ArchiveFormat;
+
+// format
+pub struct ImgVersion3Unencrypted;
+
+impl ArchiveFormat for ImgVersion3Unencrypted
+{
+	fn get_format_type(&self) -> FormatType
+	{
+		FormatType::IMG
+	}
+	
+	fn get_version(&self) -> u8
+	{
+		3
+	}
+	
+	fn get_name(&self) -> &'static str
+	{
+		"IMG version 3 (unencrypted)"
+	}
+	
+	fn detect(&self, header: &[u8]) -> bool
+	{
+		header.len() >= 4 && header[0..4] == u32::to_le_bytes(0xA94E2A52)
+	}
+	
+	fn parse(&self, format: &mut Format, mut reader: &mut dyn ReadSeek) -> Result<(), ImgError>
+	{
+		parse_list(format, &mut reader)
+	}
+	
+	fn save(&self, format: &mut Format, img_path_out: &str, _dir_path_out: &str) -> Result<(), ImgError>
+	{
+		save_list(format, img_path_out)
+	}
+	
+	fn get_header_size(&self) -> u64
+	{
+		20
+	}
+	
+	fn get_directory_entry_size(&self) -> u64
+	{
+		16
+	}
+	
+	fn get_names_size(&self, format: &Format) -> u64
+	{
+		format.get_names_len_for_v3()
+	}
+	
+	fn supports_resource_types(&self) -> bool
+	{
+		true
+	}
+}
+
+// parse
+pub fn parse_list<R: Read + Seek>(format: &mut super::super::Format, reader: &mut R) -> Result<(), ImgError>
+{
+	let file_size = crate::utility::get_reader_size(reader)?;
+	reader.seek(SeekFrom::Start(0))?;
+	let mut reader = BufReader::new(reader);
+	
+	let mut buffer = vec![0; 20];
+	reader.read_exact(&mut buffer).map_err(ImgError::header)?;
+	
+	// header
+	let buf1 = [buffer[0], buffer[1], buffer[2], buffer[3]];
+	let buf2 = [buffer[4], buffer[5], buffer[6], buffer[7]];
+	let buf3 = [buffer[8], buffer[9], buffer[10], buffer[11]];
+	let buf4 = [buffer[12], buffer[13], buffer[14], buffer[15]];
+	let buf5 = [buffer[16], buffer[17]];
+	let buf6 = [buffer[18], buffer[19]];
+	
+	let _file_stamp = u32::from_le_bytes(buf1);
+	let _file_version = u32::from_le_bytes(buf2);
+	let entry_count = u32::from_le_bytes(buf3);
+	let _table_data_size = u32::from_le_bytes(buf4);
+	let _table_item_data_size = u16::from_le_bytes(buf5);
+	let _unknown1 = u16::from_le_bytes(buf6);
+	
+	// directory I - offset / size
+	buffer.resize(16, 0);
+	
+	//let table_entry_count = table_data_size / (table_item_data_size as u32);
+	//let mut seek = 20;
+	
+	crate::utility::check_directory_range(file_size, 20, entry_count as u64, 16)?;
+	
+	format.entries = Vec::with_capacity(entry_count as usize);
+	
+	for i in 0..entry_count
+	{
+		reader.read_exact(&mut buffer).map_err(ImgError::directory)?;
+		
+		let buf1 = [buffer[0], buffer[1], buffer[2], buffer[3]];
+		let buf2 = [buffer[4], buffer[5], buffer[6], buffer[7]];
+		let buf3 = [buffer[8], buffer[9], buffer[10], buffer[11]];
+		let buf4 = [buffer[12], buffer[13]];
+		let buf5 = [buffer[14], buffer[15]];
+		
+		let item_size = u32::from_le_bytes(buf1);
+		let resource_type = u32::from_le_bytes(buf2);
+		let offset = u32::from_le_bytes(buf3);
+		let size = u16::from_le_bytes(buf4);
+		let flags = u16::from_le_bytes(buf5);
+		
+		let offset2 = crate::utility::sectors_to_bytes(offset as u64);
+		let size2 = crate::utility::sectors_to_bytes(size as u64);
+		
+		let entry = super::super::entry::Entry
+		{
+			index: i,
+			offset_in: offset2,
+			offset_out: offset2,
+			size: size2,
+			name: String::from(""),
+			data_temp_path: String::from(""),
+			streaming_size: size2,
+			size_in_archive: 0,
+			resource_type: resource_type,
+			resource_flags: item_size,
+			flags: flags,
+			rpf: None
+		};
+		format.entries.push(entry);
+		
+		//seek += table_item_data_size as usize;
+	}
+	
+	// directory II - entry names
+	//buffer.resize(4096, 0);
+	//buffer.clear();
+	for i in 0..entry_count
+	{
+		buffer.clear();
+		//println!("seek: {}", reader.seek(SeekFrom::Current(0)).unwrap());
+		let read = reader.read_until(0, &mut buffer)?;
+		if read == 0
+		{
+			return Err(ImgError::DirectoryOutOfRange);
+		}
+		//println!("{} {}", read, buffer.len());
+		
+		format.entries[i as usize].name = crate::name_codec::decode(&buffer);
+	}
+	
+	Ok(())
+}
+
+// save
+pub fn save_list(format: &mut super::super::Format, img_path_out: &str) -> Result<(), ImgError>
+{
+	super::check_entry_offsets(format, 20 + (format.entries.len() as u64) * 16 + format.get_names_len_for_v3())?;
+	
+	let img_uses_temp_path_out : bool = format.img_path_in == img_path_out;
+	
+	let mut seek : u64 = 0;
+	
+	let img_temp_path_out : String = crate::utility::get_next_file_path2(format.get_saving_dir()?, "temp.img".to_string());
+	let img_path_out2 = if img_uses_temp_path_out
+	{
+		img_temp_path_out
+	}
+	else
+	{
+		img_path_out.to_string()
+	};
+	
+	let file_out = File::create(&img_path_out2)?;
+	let mut buffer_out = BufWriter::new(file_out);
+	
+	let mut buffer : Vec<u8> = Vec::new();
+	let mut names_buffer : Vec<u8> = Vec::new();
+	
+	// header
+	let names_len_v3 = format.get_names_len_for_v3();
+	let entry_count = format.entries.len() as u64;
+	let table_data_size = crate::utility::to_sector_bytes((16 * entry_count) + names_len_v3);
+	let body_start = 20 + (16 * entry_count) + names_len_v3;
+	
+	buffer.extend(&0xA94E2A52u32.to_le_bytes());
+	buffer.extend(&3u32.to_le_bytes());
+	buffer.extend(&crate::utility::to_u32_field(entry_count, "entry count")?.to_le_bytes());
+	buffer.extend(&crate::utility::to_u32_field(table_data_size, "table data size")?.to_le_bytes());
+	buffer.extend(&16u16.to_le_bytes());
+	buffer.extend(&0u16.to_le_bytes());
+	
+	seek += 20;
+	buffer_out.write_all(buffer.as_slice())?;
+	
+	// directory - most entry info
+	for i in 0..entry_count
+	{
+		buffer.clear();
+		
+		let entry = format.get_entry_by_index(i).unwrap();
+		
+		buffer.extend(&super::get_version3_item_size(entry)?.to_le_bytes());
+		buffer.extend(&entry.resource_type.to_le_bytes());
+		buffer.extend(&crate::utility::to_u32_field(entry.get_offset_out_sectors(), "entry offset in sectors")?.to_le_bytes());
+		buffer.extend(&crate::utility::to_u16_field(entry.get_size_sectors(), "entry size in sectors")?.to_le_bytes());
+		
+		buffer.extend(&super::get_version3_flags(entry).to_le_bytes());
+		
+		seek += 16;
+		buffer_out.write_all(buffer.as_slice())?;
+		
+		names_buffer.extend(crate::name_codec::encode(&entry.name)?);
+		names_buffer.push(0);
+	}
+	
+	// directory - entry names
+	buffer_out.write_all(names_buffer.as_slice())?;
+	seek += names_buffer.len() as u64;
+	names_buffer.clear();
+	
+	// padding after directory
+	buffer.clear();
+	if body_start % 2048 != 0 && entry_count > 0
+	{
+		let pad_data_size = 2048 - (body_start % 2048);
+		crate::utility::write_zeros(&mut buffer, pad_data_size);
+		seek += pad_data_size;
+		buffer_out.write_all(buffer.as_slice())?;
+	}
+	
+	// entry data
+	let img_is_new = format.is_new();
+	
+	let mut reader : Option<BufReader<File>> = None;
+	
+	if !img_is_new
+	{
+		reader = Some(BufReader::new(File::open(&format.img_path_in)?));
+	}
+	
+	let entries : Vec<super::super::entry::Entry> = format.get_entries_sorted_by_offset_out();
+	for entry in entries
+	{
+		// pad entry gaps
+		let entry_offset = entry.offset_out;
+		if seek < entry_offset
+		{
+			let diff = entry_offset - seek;
+			crate::utility::write_zeros_to(&mut buffer_out, diff)?;
+			seek += diff;
+		}
+		
+		// copy entry data
+		let data_size = if img_is_new
+		{
+			format.copy_entry_data(&entry, &mut buffer_out)?
+		}
+		else
+		{
+			entry.copy_data_with_reader(reader.as_mut().unwrap(), &mut buffer_out)?
+		};
+		seek += data_size;
+		
+		// pad entry data
+		if seek % 2048 != 0
+		{
+			let remainder = 2048 - (seek % 2048);
+			crate::utility::write_zeros_to(&mut buffer_out, remainder)?;
+			seek += remainder;
+		}
+	}
+	
+	buffer_out.flush()?;
+	drop(buffer_out);
+	
+	if img_uses_temp_path_out
+	{
+		crate::utility::move_file_over(&img_path_out2, img_path_out)?;
+	}
+	
+	Ok(())
+}
\ No newline at end of file
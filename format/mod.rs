@@ -0,0 +1,1648 @@
+extern crate dirs;
+
+use std::collections::HashMap;
+use std::fs;
+use std::fs::File;
+use std::io::BufReader;
+use std::io::Cursor;
+use std::io::Read;
+use std::io::Seek;
+use std::io::Write;
+use std::str;
+use std::path::Path;
+use std::process;
+
+use super::error::ImgError;
+use super::keystore::Keystore;
+
+pub mod archive_format;
+pub mod format_detector;
+pub mod entry;
+pub mod entry_tree;
+pub mod img;
+pub mod resource;
+pub mod rpf;
+
+
+use entry::Entry as Entry;
+use archive_format::ArchiveFormat;
+
+
+
+
+
+
+pub const LOCAL_DATA_FOLDER_NAME	: &str = "IMG Desk";
+
+const TEMP_FOLDER_NAME				: &str = "Temp";
+
+const TEMP_SAVING_FOLDER_NAME		: &str = "Saving";
+const TEMP_ENTRYDATA_FOLDER_NAME	: &str = "EntryData";
+const TEMP_UNDO_FOLDER_NAME			: &str = "Undo";
+const TEMP_NEW_FOLDER_NAME			: &str = "New";
+
+
+
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum FormatType
+{
+	UNKNOWN,
+	IMG,
+	RPF
+}
+
+// an archive source that isn't a file on disk, e.g. a memory buffer
+pub trait ReadSeek: Read + Seek {}
+impl<T: Read + Seek> ReadSeek for T {}
+
+// IMG Desk's folder in the user's data folder, None when the system has no data folder or its path isn't UTF-8
+pub fn get_local_data_dir() -> Option<String>
+{
+	let base = dirs::data_local_dir()?;
+	Some(format!("{}/{}/", base.to_str()?, LOCAL_DATA_FOLDER_NAME))
+}
+
+
+
+
+
+pub struct Format
+{
+	pub dir_path_in: String,
+	pub img_path_in: String,
+	pub entries: Vec<Entry>,
+	pub format_type: FormatType,
+	pub img_version: u8,
+	pub img_encrypted: bool,
+	pub source: Option<Box<dyn ReadSeek>>,
+	
+	// the archive file mapped into memory, when built with the mmap feature
+	#[cfg(feature = "mmap")]
+	pub map: Option<memmap2::Mmap>,
+	
+	// names for archives that only store name hashes, by hash
+	pub hash_names: HashMap<u32, String>,
+	
+	// keys for encrypted archives
+	pub keys: Keystore,
+	
+	// deflate uncompressed binary entries when saving, for archives that support it
+	pub compress_entries: bool,
+	
+	// RPF versions 2 to 4: the unknown header value after the entry count, written back as it was read
+	pub rpf_header_unknown: u32,
+	
+	// RPF: byte order of the header and TOC, big endian for console archives
+	pub rpf_byte_order: rpf::ByteOrder,
+	
+	// GTA V NG keys depend on the archive's name, this is the entry name of an archive opened from another, otherwise the file name is used
+	pub ng_name: String
+}
+
+impl Default for Format
+{
+	fn default() -> Self
+	{
+		Self
+		{
+			dir_path_in: Default::default(),
+			img_path_in: Default::default(),
+			entries: Default::default(),
+			format_type: FormatType::UNKNOWN,
+			img_version: 0,
+			img_encrypted: false,
+			source: None,
+			#[cfg(feature = "mmap")]
+			map: None,
+			hash_names: HashMap::new(),
+			keys: Keystore::default(),
+			compress_entries: false,
+			rpf_header_unknown: 0,
+			rpf_byte_order: rpf::ByteOrder::Little,
+			ng_name: String::from("")
+		}
+	}
+}
+
+impl Format
+{
+	#[allow(clippy::new_ret_no_self)]
+	pub fn new(&mut self, img_path_in: &str, dir_path_in: &str) -> Result<(), ImgError>
+	{
+		self.init_working_dir()?;
+		
+		self.img_path_in = img_path_in.to_string();
+		self.dir_path_in = dir_path_in.to_string();
+		
+		self.format_type = FormatType::IMG;
+		self.img_version = 1;
+		self.img_encrypted = false;
+		
+		Ok(())
+	}
+	
+	pub fn parse(&mut self, img_path_in: &str, dir_path_in: &str) -> Result<(), ImgError>
+	{
+		self.init_working_dir()?;
+		
+		let archive_format = format_detector::detect_version(&img_path_in.to_string(), &self.keys)?;
+		
+		// set before parsing, as GTA V NG keys depend on the file name
+		self.img_path_in = img_path_in.to_string();
+		self.dir_path_in = dir_path_in.to_string();
+		
+		if archive_format.needs_dir_file()
+		{
+			let mut dir_reader = BufReader::new(File::open(dir_path_in)?);
+			archive_format.parse(self, &mut dir_reader)?;
+		}
+		else
+		{
+			let mut reader = BufReader::new(File::open(img_path_in)?);
+			archive_format.parse(self, &mut reader)?;
+		}
+		
+		self.set_archive_format(archive_format.as_ref());
+		self.source = None;
+		self.map_archive()?;
+		
+		Ok(())
+	}
+	
+	pub fn parse_from<R: Read + Seek + 'static>(&mut self, mut reader: R) -> Result<(), ImgError>
+	{
+		self.init_working_dir()?;
+		
+		let archive_format = format_detector::detect_version_from(&mut reader, &self.keys)?;
+		
+		reader.seek(std::io::SeekFrom::Start(0))?;
+		archive_format.parse(self, &mut reader)?;
+		
+		self.img_path_in = String::from("");
+		self.dir_path_in = String::from("");
+		self.set_archive_format(archive_format.as_ref());
+		self.source = Some(Box::new(reader));
+		self.unmap_archive();
+		
+		Ok(())
+	}
+	
+	pub fn parse_version1_from<R: Read + Seek + 'static, D: Read + Seek>(&mut self, reader: R, mut dir_reader: D) -> Result<(), ImgError>
+	{
+		self.init_working_dir()?;
+		
+		let archive_format = img::version1::ImgVersion1;
+		archive_format.parse(self, &mut dir_reader)?;
+		
+		self.img_path_in = String::from("");
+		self.dir_path_in = String::from("");
+		self.set_archive_format(&archive_format);
+		self.source = Some(Box::new(reader));
+		self.unmap_archive();
+		
+		Ok(())
+	}
+	
+	pub fn save(&mut self, img_path_out: &str, dir_path_out: &str) -> Result<(), ImgError>
+	{
+		let archive_format = match self.get_archive_format()
+		{
+			Some(archive_format) => archive_format,
+			None => return Err(ImgError::Unsupported(format!("saving {:?} version {}", self.format_type, self.img_version)))
+		};
+		
+		// the mapping is dropped while saving, as a mapped file can't be replaced on Windows, then maps the saved archive
+		self.unmap_archive();
+		let result = archive_format.save(self, img_path_out, dir_path_out);
+		
+		// entry data is read from the saved archive from now on, as the entry offsets only match it
+		if result.is_ok()
+		{
+			for entry in self.entries.iter_mut()
+			{
+				entry.offset_in = entry.offset_out;
+			}
+			
+			self.img_path_in = img_path_out.to_string();
+			self.dir_path_in = dir_path_out.to_string();
+			self.source = None;
+		}
+		
+		let map_result = self.map_archive();
+		result?;
+		map_result
+	}
+	
+	// an entry that is itself an archive, read straight from this archive's file when the entry is stored as is
+	pub fn open_child(&mut self, entry_index: u64) -> Result<Format, ImgError>
+	{
+		let entry = match self.get_entry_by_index(entry_index)
+		{
+			Some(entry) => entry.clone(),
+			None => return Err(ImgError::Io(std::io::Error::new(std::io::ErrorKind::NotFound, format!("no entry at index {}", entry_index))))
+		};
+		
+		// the child has no file, its bytes stay in memory
+		let mut child = Format
+		{
+			ng_name: match &entry.rpf
+			{
+				Some(rpf) => rpf.full_name.clone(),
+				None => entry.get_path()
+			},
+			hash_names: self.hash_names.clone(),
+			keys: self.keys.clone(),
+			compress_entries: self.compress_entries,
+			..Default::default()
+		};
+		
+		let is_stored_as_is = match &entry.rpf
+		{
+			Some(rpf) => !rpf.is_compressed && rpf.encryption == 0,
+			None => true
+		};
+		
+		if is_stored_as_is && entry.data_temp_path.is_empty() && self.source.is_none() && !self.img_path_in.is_empty()
+		{
+			let file = File::open(&self.img_path_in)?;
+			child.parse_from(super::utility::RangeReader::new(file, entry.offset_in, entry.size))?;
+		}
+		else
+		{
+			let data = self.get_entry_file_data(&entry)?;
+			child.parse_from(Cursor::new(data))?;
+		}
+		
+		Ok(child)
+	}
+	
+	// save a child archive from open_child, writing its bytes back into the entry
+	pub fn save_child(&mut self, entry_index: u64, child: &mut Format) -> Result<(), ImgError>
+	{
+		let img_path_out = super::utility::get_next_file_path2(self.get_saving_dir()?, "child.img".to_string());
+		let dir_path_out = match super::utility::replace_file_extension(&img_path_out, "dir")
+		{
+			Some(dir_path_out) => dir_path_out,
+			None => return Err(ImgError::InvalidName(img_path_out))
+		};
+		
+		child.save(&img_path_out, &dir_path_out)?;
+		child.unmap_archive();
+		let data = super::utility::get_file_data(img_path_out.clone())?;
+		fs::remove_file(&img_path_out)?;
+		
+		// the saved entry offsets only match the saved bytes, which are only kept in memory
+		child.img_path_in = String::from("");
+		child.dir_path_in = String::from("");
+		child.source = Some(Box::new(Cursor::new(data.clone())));
+		
+		self.set_entry_data(entry_index, data)
+	}
+	
+	pub fn get_archive_format(&self) -> Option<Box<dyn ArchiveFormat>>
+	{
+		archive_format::get_archive_format(self.format_type, self.img_version, self.img_encrypted)
+	}
+	
+	fn set_archive_format(&mut self, archive_format: &dyn ArchiveFormat)
+	{
+		self.format_type = archive_format.get_format_type();
+		self.img_version = archive_format.get_version();
+		self.img_encrypted = archive_format.is_encrypted();
+	}
+	
+	pub fn reset(&mut self)
+	{
+		self.dir_path_in = String::from("");
+		self.img_path_in = String::from("");
+		self.format_type = FormatType::UNKNOWN;
+		self.img_version = 0;
+		self.img_encrypted = false;
+		self.entries = Vec::new();
+		self.source = None;
+		self.rpf_header_unknown = 0;
+		self.rpf_byte_order = rpf::ByteOrder::Little;
+		self.ng_name = String::from("");
+		self.unmap_archive();
+	}
+	
+	// memory map
+	// map the archive file into memory, so entry data is read from it without opening the file each time
+	pub fn map_archive(&mut self) -> Result<(), ImgError>
+	{
+		self.unmap_archive();
+		
+		#[cfg(feature = "mmap")]
+		{
+			if self.source.is_some() || !Path::new(&self.img_path_in).is_file()
+			{
+				return Ok(());
+			}
+			
+			// an empty file can't be mapped
+			let file = File::open(&self.img_path_in)?;
+			if file.metadata()?.len() == 0
+			{
+				return Ok(());
+			}
+			
+			// the archive must not be changed by other programs while it is mapped
+			self.map = Some(unsafe { memmap2::Mmap::map(&file)? });
+		}
+		
+		Ok(())
+	}
+	
+	pub fn unmap_archive(&mut self)
+	{
+		#[cfg(feature = "mmap")]
+		{
+			self.map = None;
+		}
+	}
+	
+	// entry data straight from the mapped archive without copying, None when the archive isn't mapped, the entry's data is in a temp file, or the data runs past the end of the file
+	pub fn get_mapped_entry_data(&self, entry: &Entry) -> Option<&[u8]>
+	{
+		#[cfg(feature = "mmap")]
+		{
+			let map = self.map.as_ref()?;
+			if !entry.data_temp_path.is_empty()
+			{
+				return None;
+			}
+			
+			let end = entry.offset_in.checked_add(entry.size)?;
+			if end > map.len() as u64
+			{
+				return None;
+			}
+			return Some(&map[entry.offset_in as usize..end as usize]);
+		}
+		
+		#[cfg(not(feature = "mmap"))]
+		{
+			let _ = entry;
+			return None;
+		}
+	}
+	
+	pub fn init_working_dir(&mut self) -> Result<(), ImgError>
+	{
+		let saving_dir : String = self.get_saving_dir()?;
+		let entry_data_dir : String = self.get_entry_data_dir()?;
+		let undo_dir : String = self.get_undo_dir()?;
+		let new_dir : String = self.get_new_dir()?;
+		
+		fs::create_dir_all(saving_dir)?;
+		fs::create_dir_all(entry_data_dir)?;
+		fs::create_dir_all(undo_dir)?;
+		fs::create_dir_all(new_dir)?;
+		Ok(())
+	}
+	
+	fn get_working_dir(&mut self) -> Result<String, ImgError>
+	{
+		match get_local_data_dir()
+		{
+			Some(base) => Ok(base),
+			None => Err(ImgError::Io(std::io::Error::new(std::io::ErrorKind::NotFound, "no local data folder to keep temporary files in")))
+		}
+	}
+	
+	fn get_temp_dir(&mut self) -> Result<String, ImgError>
+	{
+		let base = self.get_working_dir()?;
+		Ok(format!("{}{}/{}/", base, TEMP_FOLDER_NAME, process::id().to_string().as_str()))
+	}
+	
+	fn get_saving_dir(&mut self) -> Result<String, ImgError>
+	{
+		let base = self.get_temp_dir()?;
+		Ok(format!("{}{}/", base, TEMP_SAVING_FOLDER_NAME))
+	}
+	
+	fn get_entry_data_dir(&mut self) -> Result<String, ImgError>
+	{
+		let base = self.get_temp_dir()?;
+		Ok(format!("{}{}/", base, TEMP_ENTRYDATA_FOLDER_NAME))
+	}
+	
+	pub fn get_undo_dir(&mut self) -> Result<String, ImgError>
+	{
+		let base = self.get_temp_dir()?;
+		Ok(format!("{}{}/", base, TEMP_UNDO_FOLDER_NAME))
+	}
+	
+	pub fn get_new_dir(&mut self) -> Result<String, ImgError>
+	{
+		let base = self.get_temp_dir()?;
+		Ok(format!("{}{}/", base, TEMP_NEW_FOLDER_NAME))
+	}
+	
+	pub fn remove_temp_dir(&mut self)
+	{
+		let temp_dir = match self.get_temp_dir()
+		{
+			Ok(temp_dir) => temp_dir,
+			Err(_) => return
+		};
+		
+		if !temp_dir.contains(LOCAL_DATA_FOLDER_NAME)
+		{
+			return;
+		}
+		
+		if !temp_dir.contains("Temp")
+		{
+			return;
+		}
+		
+		if !Path::is_dir(Path::new(&temp_dir))
+		{
+			return;
+		}
+		
+		// nothing more to do when it fails, it is only the temp folder
+		let _ = fs::remove_dir_all(temp_dir);
+	}
+	
+	fn get_next_lowest_offset(&mut self, new_data_size: u64) -> u64
+	{
+		if self.entries.is_empty()
+		{
+			crate::utility::to_sector_bytes(self.get_img_header_size() + self.get_img_directory_entry_size())
+		}
+		else
+		{
+			// fetch offset and size for all entries
+			let mut ranges = Vec::new();
+			
+			let j = self.entries.len();
+			for i in 0..j
+			{
+				let entry = self.entries[i].clone();
+				
+				ranges.push((entry.offset_out, entry.size));
+			}
+			
+			// sort offset and size vector by offset
+			ranges.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+			
+			// check if new file size will fit before any existing entry
+			let mut offset : u64 = self.get_entry_data_offset();
+			for (offset2, size2) in &ranges
+			{
+				if *offset2 >= offset && new_data_size <= *offset2 - offset
+				{
+					return offset;
+				}
+				
+				offset = *offset2 + *size2;
+			}
+			
+			// add after last entry
+			offset
+		}
+	}
+	
+	fn get_next_lowest_offset_excluding_entry(&mut self, new_data_size: u64, exclude_entry_index: u32) -> u64
+	{
+		if self.entries.is_empty()
+		{
+			0
+		}
+		else
+		{
+			// fetch offset and size for all entries
+			let mut ranges = Vec::new();
+			
+			let j = self.entries.len();
+			for i in 0..j
+			{
+				let entry = self.entries[i].clone();
+				
+				if entry.index == exclude_entry_index
+				{
+					continue;
+				}
+				
+				ranges.push((entry.offset_out, entry.size));
+			}
+			
+			// sort offset and size vector by offset
+			ranges.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+			
+			// check if new file size will fit before any existing entry
+			let mut offset : u64 = self.get_entry_data_offset();
+			for (offset2, size2) in &ranges
+			{
+				if *offset2 >= offset && new_data_size <= *offset2 - offset
+				{
+					return offset;
+				}
+				
+				offset = *offset2 + *size2;
+			}
+			
+			// add after last entry
+			offset
+		}
+	}
+	
+	pub fn get_entry_offsets(&mut self) -> Vec<u64>
+	{
+		let mut entry_offsets : Vec<u64> = Vec::new();
+		
+		for entry in self.entries.iter()
+		{
+			entry_offsets.push(entry.offset_out);
+		}
+		
+		entry_offsets
+	}
+	
+	pub fn recalculate_entry_offsets(&mut self)
+	{
+		let mut offset : u64 = self.get_entry_data_offset();
+		
+		for entry in self.entries.iter_mut()
+		{
+			entry.offset_out = offset;
+			
+			offset += crate::utility::to_sector_bytes(entry.size);
+		}
+	}
+	
+	pub fn set_entry_offsets(&mut self, entry_offsets: &Vec<u64>)
+	{
+		for (entry, offset) in self.entries.iter_mut().zip(entry_offsets.iter())
+		{
+			entry.offset_out = *offset;
+		}
+	}
+	
+	pub fn get_entry_data_offset(&mut self) -> u64
+	{
+		let header_size = self.get_img_header_size();
+		let directory_size = self.get_img_directory_entry_size();
+		let names_size = self.get_img_names_size();
+		
+		let first_entry_offset = header_size + directory_size + names_size;
+		crate::utility::to_sector_bytes(first_entry_offset)
+	}
+	
+	pub fn get_img_header_size(&mut self) -> u64
+	{
+		match self.get_archive_format()
+		{
+			Some(archive_format) => archive_format.get_header_size(),
+			None => 0
+		}
+	}
+	
+	pub fn get_img_directory_entry_size(&mut self) -> u64
+	{
+		match self.get_archive_format()
+		{
+			Some(archive_format) => archive_format.get_directory_entry_size(),
+			None => 32
+		}
+	}
+	
+	pub fn get_img_directory_size(&mut self) -> u64
+	{
+		self.get_img_directory_entry_size() * (self.entries.len() as u64)
+	}
+	
+	pub fn get_img_names_size(&mut self) -> u64
+	{
+		match self.get_archive_format()
+		{
+			Some(archive_format) => archive_format.get_names_size(self),
+			None => 0
+		}
+	}
+	
+	pub fn supports_resource_types(&self) -> bool
+	{
+		match self.get_archive_format()
+		{
+			Some(archive_format) => archive_format.supports_resource_types(),
+			None => false
+		}
+	}
+	
+	pub fn supports_folders(&self) -> bool
+	{
+		match self.get_archive_format()
+		{
+			Some(archive_format) => archive_format.supports_folders(),
+			None => false
+		}
+	}
+	
+	pub fn get_max_entry_name_length(&mut self) -> usize
+	{
+		match self.get_archive_format()
+		{
+			Some(archive_format) => archive_format.get_max_entry_name_length(),
+			None => 24
+		}
+	}
+	
+	// a new or renamed entry's name, without its folder path
+	pub fn check_entry_name(&mut self, name: &str) -> Result<(), ImgError>
+	{
+		crate::name_codec::check_name(name)?;
+		
+		let max_entry_name_length = self.get_max_entry_name_length();
+		if crate::name_codec::get_encoded_len(name) > max_entry_name_length
+		{
+			return Err(ImgError::Unsupported(format!("entry name \"{}\" of more than {} characters", name, max_entry_name_length)));
+		}
+		Ok(())
+	}
+	
+	pub fn add_file(&mut self, path: String) -> Result<Entry, ImgError>
+	{
+		self.add_file_at(path, -1, String::from(""))
+	}
+	
+	pub fn add_file_at(&mut self, path: String, entry_index: i32, entry_name: String) -> Result<Entry, ImgError>
+	{
+		let entry_name2 = if entry_name.is_empty()
+		{
+			match super::utility::get_file_name(path.as_str())
+			{
+				Some(file_name) => file_name.to_string(),
+				None => return Err(ImgError::InvalidName(path))
+			}
+		}
+		else
+		{
+			entry_name
+		};
+		let data = super::utility::get_file_data(path)?;
+		self.add_data_at(entry_name2, data, entry_index)
+	}
+	
+	pub fn add_data(&mut self, name: String, data: Vec<u8>) -> Result<Entry, ImgError>
+	{
+		self.add_data_at(name, data, -1)
+	}
+	
+	pub fn add_data_at(&mut self, name: String, data: Vec<u8>, entry_index: i32) -> Result<Entry, ImgError>
+	{
+		// archives with folders take a path, e.g. "a/b/c.wdr"
+		let (folder_path, name) = if self.supports_folders()
+		{
+			entry_tree::split_path(&entry_tree::get_entry_path(&name))
+		}
+		else
+		{
+			(String::from(""), name)
+		};
+		
+		self.check_entry_name(&name)?;
+		crate::name_codec::encode(&folder_path)?;
+		
+		let data_temp_path : String =
+		{
+			super::utility::get_next_file_path2(self.get_entry_data_dir()?, super::utility::get_data_file_name(&name))
+		};
+		
+		let entry_offset = self.get_next_lowest_offset(data.len() as u64);
+		
+		let offset = crate::utility::to_sector_bytes(entry_offset);
+		
+		let rpf = if self.format_type == FormatType::RPF
+		{
+			Some(entry::RpfEntry
+			{
+				folder_path: folder_path,
+				full_name: name.clone(),
+				uncompressed_size: data.len() as u32,
+				..Default::default()
+			})
+		}
+		else
+		{
+			None
+		};
+		
+		// RPF entries keep their resource info in rpf, IMG entries keep it for every version so it is saved after a change to version 3
+		let (resource_type, resource_flags) = if rpf.is_none()
+		{
+			resource::get_resource_info(&name, &data)
+		}
+		else
+		{
+			(0, 0)
+		};
+		
+		let size = crate::utility::to_sector_bytes(data.len() as u64);
+		
+		let entry = Entry
+		{
+			index: self.entries.len() as u32,
+			offset_in: offset,
+			offset_out: offset,
+			size: size,
+			name: name.clone(),
+			data_temp_path: data_temp_path.clone(),
+			streaming_size: size,
+			size_in_archive: 0,
+			resource_type: resource_type,
+			resource_flags: resource_flags,
+			flags: 0,
+			rpf: rpf
+		};
+		
+		crate::utility::set_file_data(data_temp_path, &data)?;
+		
+		if entry_index == -1
+		{
+			self.entries.push(entry);
+		}
+		else
+		{
+			self.entries.insert(entry_index as usize, entry);
+		}
+		
+		if entry_index == -1
+		{
+			let len = self.entries.len();
+			Ok(self.entries[len - 1].clone())
+		}
+		else
+		{
+			Ok(self.entries[entry_index as usize].clone())
+		}
+	}
+	
+	pub fn replace_file(&mut self, file_path: String) -> Result<Entry, ImgError>
+	{
+		self.replace_file_at(file_path, -1, String::from(""))
+	}
+	
+	pub fn replace_file_at(&mut self, file_path: String, entry_index: i32, entry_name: String) -> Result<Entry, ImgError>
+	{
+		let file_name : String = if entry_name.is_empty()
+		{
+			match crate::utility::get_file_name(file_path.as_str())
+			{
+				Some(file_name) => file_name.to_string(),
+				None => return Err(ImgError::InvalidName(file_path))
+			}
+		}
+		else
+		{
+			entry_name
+		};
+		
+		
+		
+		/*
+		let data_temp_path : String =
+		{
+			crate::utility::get_next_file_path2(self.get_entry_data_dir(), file_name.clone())
+		};
+		
+		let entry_index2 = if entry_index == -1
+		{
+			self.get_entry_by_name(file_name.clone()).unwrap().index
+		}
+		else
+		{
+			entry_index as u32
+		};
+		let entry_offset = self.get_next_lowest_offset_excluding_entry(new_file_data.len() as u64, entry_index2);
+		*/
+		
+		let entry_index2 : u64 = if entry_index == -1
+		{
+			match self.get_entry_by_name(file_name.clone())
+			{
+				Some(entry) => entry.index as u64,
+				None => return Err(ImgError::Io(std::io::Error::new(std::io::ErrorKind::NotFound, format!("no entry named \"{}\" in the archive", file_name))))
+			}
+		}
+		else
+		{
+			entry_index as u64
+		};
+		
+		let new_file_data : Vec<u8> = crate::utility::get_file_data(file_path.clone())?;
+		self.set_entry_data(entry_index2, new_file_data)?;
+		
+		/*
+		//self.check_to_remove_entry_data(entry);
+		if !entry.data_temp_path.is_empty()
+		{
+			let _ = fs::remove_file(entry.data_temp_path.as_str());
+			entry.data_temp_path = String::from("");
+		}
+		
+		{
+			entry.data_temp_path = data_temp_path.clone();
+			entry.size = crate::utility::to_sector_bytes(new_file_data.len() as u64) as u32;
+			entry.offset_out = crate::utility::to_sector_bytes(entry_offset as u64) as u32;
+		}
+		
+		crate::utility::set_file_data(data_temp_path, &new_file_data);
+		*/
+		
+		Ok(self.entries[entry_index2 as usize].clone())
+	}
+	
+	pub fn remove(&mut self, entry: &Entry) -> Result<(), ImgError>
+	{
+		let index : usize = match self.get_index_by_entry(entry)
+		{
+			Some(index) => index as usize,
+			None => return Err(ImgError::Io(std::io::Error::new(std::io::ErrorKind::NotFound, format!("no entry named \"{}\" in the archive", entry.get_path()))))
+		};
+		
+		self.check_to_remove_entry_data(&mut entry.clone());
+		self.entries.remove(index);
+		Ok(())
+	}
+	
+	fn check_to_remove_entry_data(&mut self, entry: &mut Entry)
+	{
+		if !entry.data_temp_path.is_empty()
+		{
+			let _ = fs::remove_file(entry.data_temp_path.as_str());
+			entry.data_temp_path = String::from("");
+		}
+	}
+	
+	pub fn export_entry(&mut self, folder_path: &str, entry: &mut Entry) -> Result<(), ImgError>
+	{
+		// entry paths come from the archive, so they can't be trusted to stay inside the folder
+		let entry_path = match entry_tree::get_export_path(&entry.get_path())
+		{
+			Some(entry_path) => entry_path,
+			None => return Err(ImgError::InvalidName(entry.get_path()))
+		};
+		let file_path : String = Path::new(folder_path).join(entry_path).to_string_lossy().to_string();
+		
+		// RPF entries keep their folders
+		if let Some(parent) = Path::new(&file_path).parent()
+		{
+			fs::create_dir_all(parent)?;
+		}
+		
+		// entries stored as is are written straight from the mapped archive
+		if entry.rpf.is_none()
+		{
+			if let Some(data) = self.get_mapped_entry_data(entry)
+			{
+				return super::utility::set_file_data_no_overwrite(file_path, data);
+			}
+		}
+		
+		let data = self.get_entry_file_data(entry)?;
+		super::utility::set_file_data_no_overwrite(file_path, &data)
+	}
+	
+	// export the entries in a folder and its sub folders, keeping their folders, an empty folder path exports all entries
+	pub fn export_folder(&mut self, out_folder_path: &str, folder_path: &str) -> Result<u32, ImgError>
+	{
+		let entry_indices = match self.get_entry_tree().get_folder(folder_path)
+		{
+			Some(folder) => folder.get_entry_indices_recursive(),
+			None => return Err(ImgError::Io(std::io::Error::new(std::io::ErrorKind::NotFound, format!("no folder \"{}\" in the archive", folder_path))))
+		};
+		
+		for entry_index in entry_indices.iter()
+		{
+			let mut entry = self.entries[*entry_index].clone();
+			self.export_entry(out_folder_path, &mut entry)?;
+		}
+		
+		Ok(entry_indices.len() as u32)
+	}
+	
+	// add or replace entries from a folder and its sub folders, into a folder in the archive
+	pub fn import_folder(&mut self, in_folder_path: &str, folder_path: &str) -> Result<Vec<Entry>, ImgError>
+	{
+		let folder_path2 = entry_tree::get_folder_path(folder_path);
+		let file_paths = super::utility::get_file_paths_recursive(in_folder_path)?;
+		
+		if !self.supports_folders() && (!folder_path2.is_empty() || file_paths.iter().any(|file_path| file_path.contains('/')))
+		{
+			return Err(ImgError::Unsupported(String::from("folders in IMG archives")));
+		}
+		
+		let mut entries = Vec::with_capacity(file_paths.len());
+		for file_path in file_paths.iter()
+		{
+			let entry_path = format!("{}{}", folder_path2, file_path);
+			let data = super::utility::get_file_data(format!("{}/{}", in_folder_path.trim_end_matches(['/', '\\']), file_path))?;
+			
+			let entry = match self.get_entry_index_by_path(&entry_path)
+			{
+				Some(entry_index) =>
+				{
+					self.set_entry_data(entry_index, data)?;
+					self.entries[entry_index as usize].clone()
+				},
+				None => self.add_data(entry_path, data)?
+			};
+			entries.push(entry);
+		}
+		
+		self.reassign_entry_indices();
+		
+		Ok(entries)
+	}
+	
+	// tree view of the entries, by folder
+	pub fn get_entry_tree(&self) -> entry_tree::EntryFolder
+	{
+		entry_tree::get_entry_tree(&self.entries)
+	}
+	
+	// entry lookup by path, e.g. "a/b/c.wdr", not case sensitive
+	pub fn get_entry_index_by_path(&self, path: &str) -> Option<u64>
+	{
+		let path2 = entry_tree::get_entry_path(path).to_lowercase();
+		self.entries.iter().position(|entry| entry.get_path().to_lowercase() == path2).map(|entry_index| entry_index as u64)
+	}
+	
+	pub fn get_entry_by_name(&mut self, name: String) -> Option<&mut Entry>
+	{
+		self.entries.iter_mut().find(|entry| name == entry.get_path())
+	}
+	
+	pub fn get_entry_by_index(&mut self, index: u64) -> Option<&mut Entry>
+	{
+		self.entries.get_mut(index as usize)
+	}
+	
+	pub fn get_index_by_entry(&mut self, entry2: &Entry) -> Option<u64>
+	{
+		self.entries.iter().position(|entry| entry == entry2).map(|entry_index| entry_index as u64)
+	}
+	
+	pub fn get_entry_data(&mut self, entry: &Entry) -> Result<Vec<u8>, ImgError>
+	{
+		if let Some(data) = self.get_mapped_entry_data(entry)
+		{
+			return Ok(data.to_vec());
+		}
+		
+		if entry.data_temp_path.is_empty()
+		{
+			match self.source.as_mut()
+			{
+				Some(source) => super::utility::get_file_data_range_with_reader(source, entry.offset_in, entry.size),
+				None => super::utility::get_file_data_range(self.img_path_in.clone(), entry.offset_in, entry.size)
+			}
+		}
+		else
+		{
+			super::utility::get_file_data(entry.data_temp_path.clone())
+		}
+	}
+	
+	// copy the entry's data to a writer without loading it into memory, returns the byte count
+	pub fn copy_entry_data<W: Write>(&mut self, entry: &Entry, writer: &mut W) -> Result<u64, ImgError>
+	{
+		if let Some(data) = self.get_mapped_entry_data(entry)
+		{
+			writer.write_all(data)?;
+			return Ok(data.len() as u64);
+		}
+		
+		if entry.data_temp_path.is_empty()
+		{
+			match self.source.as_mut()
+			{
+				Some(source) => super::utility::copy_file_data_range_with_reader(source, entry.offset_in, entry.size, writer),
+				None =>
+				{
+					let mut file = File::open(&self.img_path_in)?;
+					super::utility::copy_file_data_range_with_reader(&mut file, entry.offset_in, entry.size, writer)
+				}
+			}
+		}
+		else
+		{
+			super::utility::copy_file_data(entry.data_temp_path.clone(), writer)
+		}
+	}
+	
+	// entry data as a standalone file, decompressed for RPF entries
+	pub fn get_entry_file_data(&mut self, entry: &Entry) -> Result<Vec<u8>, ImgError>
+	{
+		let data = self.get_entry_data(entry)?;
+		match &entry.rpf
+		{
+			Some(rpf) => rpf::get_file_data(self, rpf, entry.resource_type, data),
+			None => Ok(data)
+		}
+	}
+	
+	pub fn set_entry_data(&mut self, entry_index: u64, data: Vec<u8>) -> Result<(), ImgError>
+	{
+		let mut entry : Entry = self.entries[entry_index as usize].clone();
+		
+		self.check_to_remove_entry_data(&mut entry);
+		
+		let data_temp_path : String = super::utility::get_next_file_path2(self.get_entry_data_dir()?, super::utility::get_data_file_name(&entry.name));
+		let entry_offset = self.get_next_lowest_offset_excluding_entry(data.len() as u64, entry.index);
+		
+		entry.set_data(data_temp_path, &data)?;
+		
+		entry.offset_out = super::utility::to_sector_bytes(entry_offset);
+		
+		self.entries[entry_index as usize] = entry;
+		
+		Ok(())
+	}
+	
+	pub fn get_entry_data_by_index(&mut self, entry_index: u64) -> Result<Vec<u8>, ImgError>
+	{
+		let entry = self.entries[entry_index as usize].clone();
+		self.get_entry_data(&entry)
+	}
+	
+	pub fn get_entry_data_by_index_with_reader<R: Read + Seek>(&mut self, entry_index: u64, reader: &mut R) -> Result<Vec<u8>, ImgError>
+	{
+		let mut entry = self.entries[entry_index as usize].clone();
+		entry.get_data_with_reader(reader)
+	}
+	
+	pub fn set_entry_index(&mut self, entry_index: u64, new_entry_index: u64)
+	{
+		let entry = self.entries.remove(entry_index as usize);
+		self.entries.insert(new_entry_index as usize, entry);
+		
+		self.reassign_entry_indices();
+	}
+	
+	// IMG version 2 directory sizes, in bytes, only the directory changes, the entry keeps its data and data size
+	pub fn set_entry_sizes(&mut self, entry_index: u64, streaming_size: u64, size_in_archive: u64)
+	{
+		let entry = &mut self.entries[entry_index as usize];
+		entry.streaming_size = streaming_size;
+		entry.size_in_archive = size_in_archive;
+	}
+	
+	pub fn reassign_entry_indices(&mut self)
+	{
+		for (index, entry) in self.entries.iter_mut().enumerate()
+		{
+			entry.index = index as u32;
+		}
+	}
+	
+	pub fn get_entry_count_by_name(&mut self, name: String) -> i32
+	{
+		let name4 = name.to_uppercase();
+		
+		let mut count = 0i32;
+		for entry in self.entries.iter_mut()
+		{
+			let name3 = entry.get_path().to_uppercase();
+			
+			if name4 == name3
+			{
+				count += 1;
+			}
+		}
+		
+		count
+	}
+	
+	pub fn get_entries_sorted_by_offset_out(&mut self) -> Vec<Entry>
+	{
+		let mut entries : Vec<Entry> = self.entries.clone();
+		
+		entries.sort_by(|a,b|a.offset_out.partial_cmp(&b.offset_out).unwrap());
+		
+		entries
+	}
+	
+	pub fn get_names_len_for_v3(&self) -> u64
+	{
+		let mut len = self.entries.len();
+		
+		for entry in self.entries.iter()
+		{
+			len += crate::name_codec::get_encoded_len(&entry.name);
+		}
+		
+		len as u64
+	}
+	
+	pub fn is_new(&mut self) -> bool
+	{
+		self.img_path_in.is_empty() || !Path::new(&self.img_path_in).is_file()
+	}
+	
+	// IMG only, an RPF archive keeps its version and folders
+	pub fn set_version(&mut self, img_version: u8, img_encrypted: bool) -> Result<(), ImgError>
+	{
+		if self.format_type != FormatType::IMG
+		{
+			return Err(ImgError::Unsupported(String::from("Setting the IMG version of an RPF archive")));
+		}
+		self.img_version = img_version;
+		self.img_encrypted = img_encrypted;
+		Ok(())
+	}
+	
+	// names to resolve name hashes with, added before parsing
+	pub fn add_hash_name(&mut self, name: &str)
+	{
+		self.hash_names.insert(super::utility::get_jenkins_hash(name), name.to_string());
+	}
+	
+	// one name per line
+	pub fn add_hash_names_from_file(&mut self, path: &str) -> Result<(), ImgError>
+	{
+		let data = super::utility::get_file_data(path.to_string())?;
+		for line in String::from_utf8_lossy(&data).lines()
+		{
+			let name = line.trim();
+			if !name.is_empty()
+			{
+				self.add_hash_name(name);
+			}
+		}
+		Ok(())
+	}
+	
+	// for RPF archives, whether the TOC is encrypted when saving
+	pub fn set_encrypted(&mut self, encrypted: bool)
+	{
+		self.img_encrypted = encrypted;
+	}
+}
+
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use std::io::Cursor;
+	
+	// the format layer runs without the GUI: a new archive is saved and parsed back
+	#[test]
+	fn new_archive_saves_and_parses_back()
+	{
+		let img_path = std::env::temp_dir().join("imgdesk_test_headless.img").to_string_lossy().to_string();
+		
+		let mut format = Format::default();
+		format.new("", "").unwrap();
+		format.img_version = 2;
+		format.add_data(String::from("a.txd"), vec![1; 2048]).unwrap();
+		format.add_data(String::from("b.dff"), vec![2; 4096]).unwrap();
+		format.recalculate_entry_offsets();
+		format.save(&img_path, "").unwrap();
+		format.reset();
+		
+		let mut format = Format::default();
+		format.parse(&img_path, "").unwrap();
+		assert_eq!(format.img_version, 2);
+		let entries = format.entries.clone();
+		assert_eq!(entries.len(), 2);
+		assert_eq!(format.get_entry_data(&entries[0]).unwrap(), vec![1; 2048]);
+		assert_eq!(format.get_entry_data(&entries[1]).unwrap(), vec![2; 4096]);
+		format.reset();
+		
+		let _ = fs::remove_file(&img_path);
+	}
+	
+	#[test]
+	fn add_and_remove_return_errors()
+	{
+		let mut format = get_format();
+		format.new("", "").unwrap();
+		assert!(matches!(format.add_file(String::from("..")), Err(ImgError::InvalidName(_))));
+		
+		let entry = format.add_data(String::from("a.txd"), vec![1; 2048]).unwrap();
+		format.remove(&entry).unwrap();
+		assert!(format.remove(&entry).is_err());
+		assert!(format.entries.is_empty());
+		format.reset();
+	}
+	
+	#[test]
+	fn archive_formats_are_found_by_their_identity()
+	{
+		for archive_format in archive_format::get_archive_formats()
+		{
+			let found = archive_format::get_archive_format(archive_format.get_format_type(), archive_format.get_version(), archive_format.is_encrypted()).unwrap();
+			assert_eq!(found.get_name(), archive_format.get_name());
+		}
+		
+		let mut format = Format
+		{
+			format_type: FormatType::IMG,
+			img_version: 4,
+			..Default::default()
+		};
+		assert!(matches!(format.save("", ""), Err(ImgError::Unsupported(_))));
+	}
+	
+	fn get_temp_path(name: &str) -> String
+	{
+		std::env::temp_dir().join(format!("imgdesk_test_{}", name)).to_string_lossy().to_string()
+	}
+	
+	fn get_entry_datas() -> Vec<(String, Vec<u8>)>
+	{
+		vec![(String::from("a.txd"), vec![1; 2048]), (String::from("b.dff"), vec![2; 4096]), (String::from("c.col"), vec![3; 2048])]
+	}
+	
+	// any key will do, as the archives are encrypted and decrypted with the same one
+	fn get_format() -> Format
+	{
+		let mut format = Format::default();
+		format.keys.add(crate::keystore::KEY_NAME_GTA_4, vec![0x5A; 32]);
+		format
+	}
+	
+	fn check_entries(format: &mut Format)
+	{
+		let entry_datas = get_entry_datas();
+		assert_eq!(format.entries.len(), entry_datas.len());
+		for (entry, (name, data)) in format.entries.clone().iter().zip(entry_datas.iter())
+		{
+			assert_eq!(&entry.get_path(), name);
+			assert_eq!(&format.get_entry_data(entry).unwrap(), data);
+		}
+	}
+	
+	// a new archive saved to a file, parsed from memory, saved again and parsed from the saved file
+	fn check_round_trip(format_type: FormatType, img_version: u8, img_encrypted: bool, file_name: &str)
+	{
+		let img_path = get_temp_path(file_name);
+		let dir_path = super::super::utility::replace_file_extension(&img_path, "dir").unwrap();
+		let img_path2 = get_temp_path(&format!("2_{}", file_name));
+		let dir_path2 = super::super::utility::replace_file_extension(&img_path2, "dir").unwrap();
+		
+		let mut format = get_format();
+		format.new("", "").unwrap();
+		format.format_type = format_type;
+		format.img_version = img_version;
+		format.img_encrypted = img_encrypted;
+		for (name, data) in get_entry_datas()
+		{
+			format.add_data(name, data).unwrap();
+		}
+		format.recalculate_entry_offsets();
+		format.save(&img_path, &dir_path).unwrap();
+		check_entries(&mut format);
+		format.reset();
+		
+		let mut format = get_format();
+		let data = fs::read(&img_path).unwrap();
+		if img_version == 1 && format_type == FormatType::IMG
+		{
+			let dir_data = fs::read(&dir_path).unwrap();
+			format.parse_version1_from(Cursor::new(data), Cursor::new(dir_data)).unwrap();
+		}
+		else
+		{
+			format.parse_from(Cursor::new(data)).unwrap();
+		}
+		assert_eq!((format.format_type, format.img_version, format.img_encrypted), (format_type, img_version, img_encrypted));
+		check_entries(&mut format);
+		format.save(&img_path2, &dir_path2).unwrap();
+		format.reset();
+		
+		let mut format = get_format();
+		format.parse(&img_path2, &dir_path2).unwrap();
+		assert_eq!((format.format_type, format.img_version, format.img_encrypted), (format_type, img_version, img_encrypted));
+		check_entries(&mut format);
+		format.reset();
+		
+		for path in [img_path, dir_path, img_path2, dir_path2].iter()
+		{
+			let _ = fs::remove_file(path);
+		}
+	}
+	
+	#[test]
+	fn img_version_1_round_trips()
+	{
+		check_round_trip(FormatType::IMG, 1, false, "round_trip_1.img");
+	}
+	
+	#[test]
+	fn img_version_2_round_trips()
+	{
+		check_round_trip(FormatType::IMG, 2, false, "round_trip_2.img");
+	}
+	
+	#[test]
+	fn img_version_3_round_trips()
+	{
+		check_round_trip(FormatType::IMG, 3, false, "round_trip_3.img");
+	}
+	
+	#[test]
+	fn img_version_3_encrypted_round_trips()
+	{
+		check_round_trip(FormatType::IMG, 3, true, "round_trip_3_encrypted.img");
+	}
+	
+	#[test]
+	fn rpf_version_2_round_trips()
+	{
+		check_round_trip(FormatType::RPF, 2, false, "round_trip_2.rpf");
+	}
+	
+	#[test]
+	fn rpf_version_7_round_trips()
+	{
+		check_round_trip(FormatType::RPF, 7, false, "round_trip_7.rpf");
+	}
+	
+	#[test]
+	fn set_version_keeps_rpf_archives()
+	{
+		let mut format = get_format();
+		format.new("", "").unwrap();
+		format.set_version(2, false).unwrap();
+		assert_eq!((format.format_type, format.img_version), (FormatType::IMG, 2));
+		
+		format.format_type = FormatType::RPF;
+		format.img_version = 7;
+		assert!(format.set_version(1, false).is_err());
+		assert_eq!((format.format_type, format.img_version), (FormatType::RPF, 7));
+		format.reset();
+	}
+	
+	// an IMG archive inside an RPF archive, edited and saved back into its entry
+	#[test]
+	fn child_archives_are_saved_into_their_entry()
+	{
+		let child_path = get_temp_path("child_in.img");
+		let parent_path = get_temp_path("child_parent.rpf");
+		
+		let mut child = get_format();
+		child.new("", "").unwrap();
+		child.img_version = 2;
+		child.add_data(String::from("a.txd"), vec![1; 2048]).unwrap();
+		child.recalculate_entry_offsets();
+		child.save(&child_path, "").unwrap();
+		let child_data = fs::read(&child_path).unwrap();
+		child.reset();
+		
+		let mut parent = get_format();
+		parent.new("", "").unwrap();
+		parent.format_type = FormatType::RPF;
+		parent.img_version = 7;
+		parent.add_data(String::from("child.img"), child_data).unwrap();
+		parent.recalculate_entry_offsets();
+		parent.save(&parent_path, "").unwrap();
+		parent.reset();
+		
+		let mut parent = get_format();
+		parent.parse(&parent_path, "").unwrap();
+		assert!(parent.open_child(1).is_err());
+		
+		let mut child = parent.open_child(0).unwrap();
+		assert_eq!(child.ng_name, "child.img");
+		child.add_data(String::from("b.dff"), vec![2; 4096]).unwrap();
+		child.recalculate_entry_offsets();
+		parent.save_child(0, &mut child).unwrap();
+		assert!(child.img_path_in.is_empty());
+		assert_eq!(child.get_entry_file_data(&child.entries[1].clone()).unwrap(), vec![2; 4096]);
+		
+		parent.recalculate_entry_offsets();
+		parent.save(&parent_path, "").unwrap();
+		parent.reset();
+		
+		let mut parent = get_format();
+		parent.parse(&parent_path, "").unwrap();
+		let mut child = parent.open_child(0).unwrap();
+		assert_eq!((child.format_type, child.img_version), (FormatType::IMG, 2));
+		let names : Vec<String> = child.entries.iter().map(|entry| entry.get_path()).collect();
+		assert_eq!(names, vec!["a.txd", "b.dff"]);
+		assert_eq!(child.get_entry_file_data(&child.entries[0].clone()).unwrap(), vec![1; 2048]);
+		assert_eq!(child.get_entry_file_data(&child.entries[1].clone()).unwrap(), vec![2; 4096]);
+		parent.reset();
+		
+		for path in [child_path, parent_path].iter()
+		{
+			let _ = fs::remove_file(path);
+		}
+	}
+	
+	// folders that only differ in case are saved as one
+	#[test]
+	fn rpf_folders_are_grouped_ignoring_case()
+	{
+		let img_path = get_temp_path("folder_case.rpf");
+		
+		let mut format = get_format();
+		format.new("", "").unwrap();
+		format.format_type = FormatType::RPF;
+		format.img_version = 2;
+		for (i, name) in ["Data/a.txt", "data/b.txt", "DATA/Sub/c.txt", "data/sub/d.txt"].iter().enumerate()
+		{
+			format.add_data(name.to_string(), vec![i as u8; 16]).unwrap();
+		}
+		assert_eq!(rpf::version2::get_toc_items(&format, false).len(), 7);
+		format.recalculate_entry_offsets();
+		format.save(&img_path, "").unwrap();
+		format.reset();
+		
+		let mut format = get_format();
+		format.parse(&img_path, "").unwrap();
+		let mut paths : Vec<String> = format.entries.iter().map(|entry| entry.get_path()).collect();
+		paths.sort();
+		assert_eq!(paths, vec!["Data/Sub/c.txt", "Data/Sub/d.txt", "Data/a.txt", "Data/b.txt"]);
+		for entry in format.entries.clone().iter()
+		{
+			let i = ["a.txt", "b.txt", "c.txt", "d.txt"].iter().position(|name| *name == entry.name).unwrap();
+			assert_eq!(format.get_entry_file_data(entry).unwrap(), vec![i as u8; 16]);
+		}
+		format.reset();
+		
+		let _ = fs::remove_file(&img_path);
+	}
+	
+	// encrypted archives are recognised without their key, and say which key is missing
+	#[test]
+	fn encrypted_archives_report_their_missing_key()
+	{
+		let img_path = get_temp_path("missing_key.img");
+		
+		let mut format = get_format();
+		format.new("", "").unwrap();
+		format.img_version = 3;
+		format.img_encrypted = true;
+		format.add_data(String::from("a.txd"), vec![1; 2048]).unwrap();
+		format.recalculate_entry_offsets();
+		format.save(&img_path, "").unwrap();
+		format.reset();
+		
+		let mut format = Format::default();
+		assert!(matches!(format.parse(&img_path, ""), Err(ImgError::MissingKey(name)) if name == crate::keystore::KEY_NAME_GTA_4));
+		format.reset();
+		
+		// RPF3 has its own key
+		let mut format = get_format();
+		format.new("", "").unwrap();
+		format.format_type = FormatType::RPF;
+		format.img_version = 3;
+		format.img_encrypted = true;
+		format.add_data(String::from("a.txt"), vec![1; 16]).unwrap();
+		assert!(matches!(format.save(&img_path, ""), Err(ImgError::MissingKey(name)) if name == crate::keystore::KEY_NAME_MCLA));
+		format.reset();
+		
+		let _ = fs::remove_file(&img_path);
+	}
+	
+	// a saved and parsed archive, for the tests below
+	fn get_saved_format(img_version: u8, file_name: &str, entry_datas: &[(&str, Vec<u8>)]) -> (Format, String)
+	{
+		let img_path = get_temp_path(file_name);
+		
+		let mut format = get_format();
+		format.new("", "").unwrap();
+		format.img_version = img_version;
+		for (name, data) in entry_datas.iter()
+		{
+			format.add_data(name.to_string(), data.clone()).unwrap();
+		}
+		format.recalculate_entry_offsets();
+		format.save(&img_path, "").unwrap();
+		format.reset();
+		
+		let mut format = get_format();
+		format.parse(&img_path, "").unwrap();
+		(format, img_path)
+	}
+	
+	#[test]
+	fn img_version_2_keeps_both_sizes()
+	{
+		let (mut format, img_path) = get_saved_format(2, "sizes.img", &[("a.dff", vec![1; 2048]), ("b.txd", vec![2; 2048])]);
+		assert_eq!((format.entries[0].streaming_size, format.entries[0].size_in_archive), (2048, 0));
+		
+		format.set_entry_sizes(1, 2048, 4096);
+		format.save(&img_path, "").unwrap();
+		format.reset();
+		
+		let mut format = get_format();
+		format.parse(&img_path, "").unwrap();
+		assert_eq!((format.entries[1].streaming_size, format.entries[1].size_in_archive), (2048, 4096));
+		assert_eq!(format.get_entry_file_data(&format.entries[1].clone()).unwrap(), vec![2; 2048]);
+		format.reset();
+		
+		let _ = fs::remove_file(&img_path);
+	}
+	
+	#[test]
+	fn img_version_3_keeps_resource_types_and_flags()
+	{
+		// virtual size 3 << 8, physical size 2 << 8
+		let resource_flags : u32 = 3 | (2 << 15);
+		let mut wdr_data = Vec::new();
+		wdr_data.extend(b"RSC\x05");
+		wdr_data.extend(&resource::RESOURCE_TYPE_MODEL.to_le_bytes());
+		wdr_data.extend(&resource_flags.to_le_bytes());
+		wdr_data.resize(2048, 1);
+		
+		let (mut format, img_path) = get_saved_format(3, "resources.img", &[("a.wdr", wdr_data.clone()), ("b.wtd", vec![2; 2048]), ("c.ipl", vec![3; 2048])]);
+		let resources : Vec<(u32, u32)> = format.entries.iter().map(|entry| (entry.resource_type, entry.resource_flags)).collect();
+		assert_eq!(resources[0], (resource::RESOURCE_TYPE_MODEL, resource_flags));
+		assert_eq!(resources[1].0, resource::RESOURCE_TYPE_TEXTURE);
+		assert_eq!(resources[2].0, resource::RESOURCE_TYPE_NONE);
+		assert_eq!(format.entries[0].get_resource_text(), "type 110, virtual 768, physical 512");
+		assert_eq!(format.entries[2].get_resource_text(), "");
+		assert_eq!(format.get_entry_file_data(&format.entries[0].clone()).unwrap(), wdr_data);
+		format.reset();
+		
+		let _ = fs::remove_file(&img_path);
+	}
+	
+	#[test]
+	fn img_version_3_keeps_long_names()
+	{
+		let long_name = format!("{}.dff", "a".repeat(300));
+		let (mut format, img_path) = get_saved_format(3, "long_names.img", &[(long_name.as_str(), vec![1; 2048])]);
+		assert_eq!(format.entries[0].get_path(), long_name);
+		assert_eq!(format.get_entry_file_data(&format.entries[0].clone()).unwrap(), vec![1; 2048]);
+		format.reset();
+		
+		let _ = fs::remove_file(&img_path);
+	}
+	
+	// mapped data is only there with the mmap feature, and follows the archive when it is saved in place
+	#[test]
+	fn mapped_entry_data_follows_the_saved_archive()
+	{
+		let (mut format, img_path) = get_saved_format(2, "mapped.img", &[("a.dff", vec![1; 2048]), ("b.txd", vec![2; 4096])]);
+		
+		let entry = format.entries[1].clone();
+		if cfg!(feature = "mmap")
+		{
+			assert_eq!(format.get_mapped_entry_data(&entry), Some(&vec![2; 4096][..]));
+		}
+		else
+		{
+			assert_eq!(format.get_mapped_entry_data(&entry), None);
+		}
+		
+		// the replaced entry grows, so the other entry moves in the saved archive
+		format.set_entry_data(0, vec![9; 6144]).unwrap();
+		format.recalculate_entry_offsets();
+		format.save(&img_path, "").unwrap();
+		
+		let entries = format.entries.clone();
+		assert_eq!(format.get_entry_data(&entries[0]).unwrap(), vec![9; 6144]);
+		assert_eq!(format.get_entry_data(&entries[1]).unwrap(), vec![2; 4096]);
+		assert_eq!(format.get_mapped_entry_data(&entries[0]), None);
+		if cfg!(feature = "mmap")
+		{
+			assert_eq!(format.get_mapped_entry_data(&entries[1]), Some(&vec![2; 4096][..]));
+		}
+		format.reset();
+		
+		let _ = fs::remove_file(&img_path);
+	}
+	
+	// entries larger than the copy buffers, copied from the parsed archive to a new one and then saved over themselves
+	#[test]
+	fn saving_streams_entry_data_from_the_archive()
+	{
+		let big_data : Vec<u8> = (0..(3 * 1024 * 1024)).map(|i| (i % 251) as u8).collect();
+		let (mut format, img_path) = get_saved_format(3, "stream.img", &[("a.dff", vec![1; 2048]), ("b.dff", big_data.clone())]);
+		let img_path2 = get_temp_path("stream_2.img");
+		
+		format.set_entry_index(1, 0);
+		format.recalculate_entry_offsets();
+		format.save(&img_path2, "").unwrap();
+		format.save(&img_path2, "").unwrap();
+		format.reset();
+		
+		let mut format = get_format();
+		format.parse(&img_path2, "").unwrap();
+		let names : Vec<String> = format.entries.iter().map(|entry| entry.get_path()).collect();
+		assert_eq!(names, vec!["b.dff", "a.dff"]);
+		assert_eq!(format.get_entry_file_data(&format.entries[0].clone()).unwrap(), big_data);
+		assert_eq!(format.get_entry_file_data(&format.entries[1].clone()).unwrap(), vec![1; 2048]);
+		
+		let mut buffer_out = Vec::new();
+		assert_eq!(format.copy_entry_data(&format.entries[0].clone(), &mut buffer_out).unwrap(), big_data.len() as u64);
+		assert_eq!(buffer_out, big_data);
+		format.reset();
+		
+		for path in [img_path, img_path2].iter()
+		{
+			let _ = fs::remove_file(path);
+		}
+	}
+}
@@ -0,0 +1,55 @@
+use std::convert::TryInto;
+
+// GTA IV resource types, the version in the RSC header
+pub const RESOURCE_TYPE_NONE : u32 = 0;
+pub const RESOURCE_TYPE_GENERIC : u32 = 0x01; // wad, whm, wpl
+pub const RESOURCE_TYPE_TEXTURE : u32 = 0x08; // wtd
+pub const RESOURCE_TYPE_BOUNDS : u32 = 0x20; // wbd, wbn
+pub const RESOURCE_TYPE_PARTICLES : u32 = 0x24; // wpfl
+pub const RESOURCE_TYPE_MODEL : u32 = 0x6E; // wdr, wdd
+pub const RESOURCE_TYPE_FRAGMENT : u32 = 0x70; // wft
+
+// resource type and RSC flags for new entry data, from the RSC header, or from the extension when there is no header
+pub fn get_resource_info(name: &str, data: &[u8]) -> (u32, u32)
+{
+	if data.len() >= 12 && &data[0..4] == b"RSC\x05"
+	{
+		let resource_type = u32::from_le_bytes(data[4..8].try_into().unwrap());
+		let resource_flags = u32::from_le_bytes(data[8..12].try_into().unwrap());
+		return (resource_type, resource_flags);
+	}
+	
+	(get_resource_type_by_extension(name), 0)
+}
+
+pub fn get_resource_type_by_extension(name: &str) -> u32
+{
+	let ext = match name.rfind('.')
+	{
+		Some(pos) => name[pos+1..].to_lowercase(),
+		None => return RESOURCE_TYPE_NONE
+	};
+	
+	match ext.as_str()
+	{
+		"wad" | "whm" | "wpl" => RESOURCE_TYPE_GENERIC,
+		"wtd" => RESOURCE_TYPE_TEXTURE,
+		"wbd" | "wbn" => RESOURCE_TYPE_BOUNDS,
+		"wpfl" => RESOURCE_TYPE_PARTICLES,
+		"wdr" | "wdd" => RESOURCE_TYPE_MODEL,
+		"wft" => RESOURCE_TYPE_FRAGMENT,
+		_ => RESOURCE_TYPE_NONE
+	}
+}
+
+// virtual (system) memory size, from the RSC flags
+pub fn get_virtual_size(resource_flags: u32) -> u64
+{
+	((resource_flags & 0x7FF) as u64) << (((resource_flags >> 11) & 0xF) + 8)
+}
+
+// physical (graphics) memory size, from the RSC flags
+pub fn get_physical_size(resource_flags: u32) -> u64
+{
+	(((resource_flags >> 15) & 0x7FF) as u64) << (((resource_flags >> 26) & 0xF) + 8)
+}
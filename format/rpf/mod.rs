@@ -0,0 +1,335 @@
+pub mod version0;
+pub mod version2;
+pub mod version3;
+pub mod version4;
+pub mod version6;
+pub mod version7;
+
+use std::convert::TryInto;
+
+use crate::error::ImgError;
+
+use super::Format;
+use super::entry::RpfEntry;
+
+// byte order of the header and TOC, PC archives are little endian and Xbox 360, PS3 and Wii archives are big endian
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ByteOrder
+{
+	Little,
+	Big
+}
+
+impl ByteOrder
+{
+	pub fn get_u32(self, buffer: &[u8], seek: usize) -> u32
+	{
+		let bytes = buffer[seek..seek+4].try_into().unwrap();
+		match self
+		{
+			ByteOrder::Little => u32::from_le_bytes(bytes),
+			ByteOrder::Big => u32::from_be_bytes(bytes)
+		}
+	}
+	
+	pub fn to_bytes(self, value: u32) -> [u8; 4]
+	{
+		match self
+		{
+			ByteOrder::Little => value.to_le_bytes(),
+			ByteOrder::Big => value.to_be_bytes()
+		}
+	}
+}
+
+// byte orders to try when parsing, in order
+pub const BYTE_ORDERS : [ByteOrder; 2] = [ByteOrder::Little, ByteOrder::Big];
+
+// the root record is a directory whose contents are in the TOC, the headers don't say which byte order they are in
+pub fn is_root_valid(toc_entries: &[TocEntry]) -> bool
+{
+	let root = match toc_entries.first()
+	{
+		Some(root) => root,
+		None => return true
+	};
+	
+	let end = (root.content_entry_index as u64) + (root.content_entry_count as u64);
+	root.is_directory && (root.content_entry_count == 0 || (root.content_entry_index >= 1 && end <= toc_entries.len() as u64))
+}
+
+// entry data as a standalone file
+pub fn get_file_data(format: &Format, rpf: &RpfEntry, resource_type: u32, mut data: Vec<u8>) -> Result<Vec<u8>, ImgError>
+{
+	if rpf.encryption != 0
+	{
+		data = version7::decrypt_file_data(rpf, data, &format.keys)?;
+	}
+	
+	if rpf.is_compressed
+	{
+		data = crate::utility::decompress_zlib_or_deflate(&data)?;
+	}
+	
+	// large GTA V resources have their size over part of the RSC7 header
+	if rpf.is_resource && version7::is_large_resource_header(&data)
+	{
+		version7::restore_large_resource_header(rpf, resource_type, &mut data);
+	}
+	
+	// GTA IV and Red Dead Redemption resources are stored without their RSC header
+	if rpf.is_resource && !(data.len() >= 3 && &data[0..3] == b"RSC")
+	{
+		let archive_format = match format.get_archive_format()
+		{
+			Some(archive_format) => archive_format,
+			None => return Err(ImgError::Unsupported(format!("RPF version {}", format.img_version)))
+		};
+		let mut data2 = archive_format.get_resource_header(format, rpf, resource_type);
+		data2.extend(data);
+		return Ok(data2);
+	}
+	
+	Ok(data)
+}
+
+// TOC
+pub struct TocEntry
+{
+	pub name_offset: u32,
+	pub is_directory: bool,
+	
+	// file
+	pub size: u32,
+	pub offset: u64,
+	pub size_in_archive: u32,
+	pub is_compressed: bool,
+	pub is_resource: bool,
+	pub resource_type: u32,
+	pub resource_flags: u32,
+	pub resource_graphics_flags: u32,
+	pub encryption: u32,
+	
+	// directory
+	pub flags: u32,
+	pub content_entry_index: u32,
+	pub content_entry_count: u32
+}
+
+// names from the name table after the TOC records
+pub fn get_toc_names(buffer: &mut Vec<u8>, toc_entries: &Vec<TocEntry>, names_offset: u64) -> Result<Vec<String>, ImgError>
+{
+	let mut names = Vec::with_capacity(toc_entries.len());
+	for toc_entry in toc_entries.iter()
+	{
+		names.push(crate::utility::get_null_string(buffer, names_offset + (toc_entry.name_offset as u64))?);
+	}
+	Ok(names)
+}
+
+// add the files in a directory record and its sub directories as entries, with their folder path
+// walked with a stack instead of recursion and each record can only be in one directory, so a crafted TOC can't loop or overflow the stack
+pub fn add_directory_entries(format: &mut super::Format, toc_entries: &[TocEntry], names: &[String], directory_index: usize, mut folder_path: String) -> Result<(), ImgError>
+{
+	let mut is_listed = vec![false; toc_entries.len()];
+	
+	// next content index, end of the contents and length of the folder path of each directory being walked
+	let mut directories : Vec<(usize, usize, usize)> = Vec::new();
+	let (start, end) = get_directory_contents(toc_entries, directory_index, &mut is_listed)?;
+	directories.push((start, end, folder_path.len()));
+	
+	while let Some(directory) = directories.last_mut()
+	{
+		let i = directory.0;
+		if i == directory.1
+		{
+			directories.pop();
+			continue;
+		}
+		directory.0 += 1;
+		folder_path.truncate(directory.2);
+		
+		let toc_entry = &toc_entries[i];
+		let name = names[i].clone();
+		
+		if toc_entry.is_directory
+		{
+			let (start, end) = get_directory_contents(toc_entries, i, &mut is_listed)?;
+			folder_path.push_str(&name);
+			folder_path.push('/');
+			directories.push((start, end, folder_path.len()));
+			continue;
+		}
+		
+		let entry = super::entry::Entry
+		{
+			index: format.entries.len() as u32,
+			offset_in: toc_entry.offset,
+			offset_out: toc_entry.offset,
+			size: toc_entry.size_in_archive as u64,
+			name: name.clone(),
+			data_temp_path: String::from(""),
+			streaming_size: toc_entry.size_in_archive as u64,
+			size_in_archive: 0,
+			resource_type: toc_entry.resource_type,
+			resource_flags: 0,
+			flags: 0,
+			rpf: Some(super::entry::RpfEntry
+			{
+				folder_path: folder_path.clone(),
+				full_name: name,
+				uncompressed_size: toc_entry.size,
+				is_compressed: toc_entry.is_compressed,
+				is_resource: toc_entry.is_resource,
+				resource_flags: toc_entry.resource_flags,
+				resource_graphics_flags: toc_entry.resource_graphics_flags,
+				encryption: toc_entry.encryption
+			})
+		};
+		format.entries.push(entry);
+	}
+	
+	Ok(())
+}
+
+// range of a directory record's contents, marked as listed
+fn get_directory_contents(toc_entries: &[TocEntry], directory_index: usize, is_listed: &mut [bool]) -> Result<(usize, usize), ImgError>
+{
+	let directory = &toc_entries[directory_index];
+	if directory.content_entry_count == 0
+	{
+		return Ok((0, 0));
+	}
+	
+	let start = directory.content_entry_index as usize;
+	let end = start + (directory.content_entry_count as usize);
+	
+	// content entries always come after their directory
+	if start <= directory_index || end > toc_entries.len()
+	{
+		return Err(ImgError::DirectoryOutOfRange);
+	}
+	
+	if is_listed[start..end].iter().any(|is_listed| *is_listed)
+	{
+		return Err(ImgError::DirectoryOutOfRange);
+	}
+	for is_listed in is_listed[start..end].iter_mut()
+	{
+		*is_listed = true;
+	}
+	
+	Ok((start, end))
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	
+	fn get_toc_entry(is_directory: bool, content_entry_index: u32, content_entry_count: u32) -> TocEntry
+	{
+		TocEntry
+		{
+			name_offset: 0,
+			is_directory: is_directory,
+			size: 4,
+			offset: 2048,
+			size_in_archive: 4,
+			is_compressed: false,
+			is_resource: false,
+			resource_type: 0,
+			resource_flags: 0,
+			resource_graphics_flags: 0,
+			encryption: 0,
+			flags: 0,
+			content_entry_index: content_entry_index,
+			content_entry_count: content_entry_count
+		}
+	}
+	
+	fn get_names(names: &[&str]) -> Vec<String>
+	{
+		names.iter().map(|name| name.to_string()).collect()
+	}
+	
+	#[test]
+	fn sub_directories_keep_their_place_and_folder()
+	{
+		let toc_entries = vec![get_toc_entry(true, 1, 3), get_toc_entry(false, 0, 0), get_toc_entry(true, 4, 2), get_toc_entry(false, 0, 0), get_toc_entry(false, 0, 0), get_toc_entry(true, 0, 0)];
+		let names = get_names(&["", "a.wdr", "b", "c.wdr", "d.wdr", "e"]);
+		
+		let mut format = crate::format::Format::default();
+		add_directory_entries(&mut format, &toc_entries, &names, 0, String::from("")).unwrap();
+		
+		let paths : Vec<String> = format.entries.iter().map(|entry| entry.get_path()).collect();
+		assert_eq!(paths, vec!["a.wdr", "b/d.wdr", "c.wdr"]);
+	}
+	
+	#[test]
+	fn directories_that_loop_or_share_contents_are_rejected()
+	{
+		let names = get_names(&["", "a", "b", "c.wdr"]);
+		let mut format = crate::format::Format::default();
+		
+		// a directory listing itself
+		let toc_entries = vec![get_toc_entry(true, 1, 1), get_toc_entry(true, 1, 1)];
+		assert!(matches!(add_directory_entries(&mut format, &toc_entries, &names, 0, String::from("")), Err(ImgError::DirectoryOutOfRange)));
+		
+		// two directories listing the same file
+		let toc_entries = vec![get_toc_entry(true, 1, 3), get_toc_entry(true, 3, 1), get_toc_entry(true, 3, 1), get_toc_entry(false, 0, 0)];
+		assert!(matches!(add_directory_entries(&mut format, &toc_entries, &names, 0, String::from("")), Err(ImgError::DirectoryOutOfRange)));
+		
+		// contents past the end of the TOC
+		let toc_entries = vec![get_toc_entry(true, 1, 4), get_toc_entry(false, 0, 0)];
+		assert!(matches!(add_directory_entries(&mut format, &toc_entries, &names, 0, String::from("")), Err(ImgError::DirectoryOutOfRange)));
+	}
+	
+	#[test]
+	fn deep_directory_chains_do_not_overflow_the_stack()
+	{
+		let depth = 100000;
+		let mut toc_entries = Vec::with_capacity(depth + 1);
+		let mut names = Vec::with_capacity(depth + 1);
+		for i in 0..depth
+		{
+			toc_entries.push(get_toc_entry(true, (i + 1) as u32, 1));
+			names.push(String::from(""));
+		}
+		toc_entries.push(get_toc_entry(false, 0, 0));
+		names.push(String::from("a.wdr"));
+		
+		let mut format = crate::format::Format::default();
+		add_directory_entries(&mut format, &toc_entries, &names, 0, String::from("")).unwrap();
+		assert_eq!(format.entries.len(), 1);
+	}
+	
+	#[test]
+	fn resources_get_the_header_of_their_archive_format()
+	{
+		let rpf = RpfEntry
+		{
+			is_resource: true,
+			resource_flags: 0x11223344,
+			resource_graphics_flags: 0x55667788,
+			..Default::default()
+		};
+		let data = vec![0xAA; 4];
+		
+		let mut format = crate::format::Format
+		{
+			format_type: crate::format::FormatType::RPF,
+			img_version: 2,
+			..Default::default()
+		};
+		let data2 = get_file_data(&format, &rpf, 1, data.clone()).unwrap();
+		assert_eq!(&data2[0..12], b"RSC\x05\x01\x00\x00\x00\x44\x33\x22\x11");
+		assert_eq!(&data2[12..], &data[..]);
+		
+		format.img_version = 6;
+		format.rpf_byte_order = ByteOrder::Big;
+		let data2 = get_file_data(&format, &rpf, 1, data.clone()).unwrap();
+		assert_eq!(&data2[0..16], b"RSC\x85\x00\x00\x00\x01\x11\x22\x33\x44\x55\x66\x77\x88");
+		assert_eq!(&data2[16..], &data[..]);
+	}
+}
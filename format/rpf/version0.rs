@@ -0,0 +1,206 @@
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+
+use crate::error::ImgError;
+
+use super::super::Format;
+use super::super::FormatType;
+use super::super::ReadSeek;
+use super::super::archive_format::ArchiveFormat;
+
+// format
+pub struct RpfVersion0;
+
+impl ArchiveFormat for RpfVersion0
+{
+	fn get_format_type(&self) -> FormatType
+	{
+		FormatType::RPF
+	}
+	
+	fn get_version(&self) -> u8
+	{
+		0
+	}
+	
+	fn get_name(&self) -> &'static str
+	{
+		"RPF version 0"
+	}
+	
+	fn detect(&self, header: &[u8]) -> bool
+	{
+		header.len() >= 4 && &header[0..4] == b"RPF0"
+	}
+	
+	fn parse(&self, format: &mut Format, mut reader: &mut dyn ReadSeek) -> Result<(), ImgError>
+	{
+		parse_list(format, &mut reader)
+	}
+	
+	fn save(&self, _format: &mut Format, _img_path_out: &str, _dir_path_out: &str) -> Result<(), ImgError>
+	{
+		Err(ImgError::Unsupported(String::from("saving RPF version 0")))
+	}
+	
+	fn get_header_size(&self) -> u64
+	{
+		2048
+	}
+	
+	fn get_directory_entry_size(&self) -> u64
+	{
+		16
+	}
+	
+	fn supports_folders(&self) -> bool
+	{
+		true
+	}
+}
+
+// parse
+pub fn parse_list<R: Read + Seek>(format: &mut super::super::Format, reader: &mut R) -> Result<(), ImgError>
+{
+	let file_size = crate::utility::get_reader_size(reader)?;
+	reader.seek(SeekFrom::Start(0))?;
+	
+	let mut buffer = [0; 12];
+	reader.read_exact(&mut buffer).map_err(ImgError::header)?;
+	
+	for byte_order in super::BYTE_ORDERS.iter()
+	{
+		let table_data_size = byte_order.get_u32(&buffer, 4);
+		let entry_count = byte_order.get_u32(&buffer, 8);
+		
+		if crate::utility::check_directory_range(file_size, 2048, table_data_size as u64, 1).is_err() || (table_data_size as u64) < (entry_count as u64) * 16
+		{
+			continue;
+		}
+		
+		// read TOC, never encrypted in this version
+		reader.seek(SeekFrom::Start(2048))?;
+		
+		let mut buffer_toc = vec![0; table_data_size as usize];
+		reader.read_exact(&mut buffer_toc).map_err(ImgError::directory)?;
+		
+		// parse TOC
+		let toc_entries = parse_toc_entries(&buffer_toc, entry_count, *byte_order)?;
+		if !super::is_root_valid(&toc_entries)
+		{
+			continue;
+		}
+		
+		format.rpf_byte_order = *byte_order;
+		format.entries = Vec::new();
+		
+		if !toc_entries.is_empty()
+		{
+			let names = super::get_toc_names(&mut buffer_toc, &toc_entries, (entry_count as u64) * 16)?;
+			super::add_directory_entries(format, &toc_entries, &names, 0, String::from(""))?;
+		}
+		
+		return Ok(());
+	}
+	
+	Err(ImgError::DirectoryOutOfRange)
+}
+
+pub fn parse_toc_entries(buffer: &Vec<u8>, entry_count: u32, byte_order: super::ByteOrder) -> Result<Vec<super::TocEntry>, ImgError>
+{
+	let mut toc_entries = Vec::with_capacity(entry_count as usize);
+	
+	for i in 0..(entry_count as usize)
+	{
+		let seek = i * 16;
+		let value1 = byte_order.get_u32(buffer, seek);
+		let value2 = byte_order.get_u32(buffer, seek + 4);
+		let value3 = byte_order.get_u32(buffer, seek + 8);
+		let value4 = byte_order.get_u32(buffer, seek + 12);
+		
+		// the directory flag is the high bit of the name offset
+		let mut toc_entry = super::TocEntry
+		{
+			name_offset: value1 & 0x7FFFFFFF,
+			is_directory: (value1 & 0x80000000) != 0,
+			size: 0,
+			offset: 0,
+			size_in_archive: 0,
+			is_compressed: false,
+			is_resource: false,
+			resource_type: 0,
+			resource_flags: 0,
+			resource_graphics_flags: 0,
+			encryption: 0,
+			flags: 0,
+			content_entry_index: 0,
+			content_entry_count: 0
+		};
+		
+		if toc_entry.is_directory
+		{
+			toc_entry.content_entry_index = value2;
+			toc_entry.content_entry_count = value3;
+		}
+		else
+		{
+			// compressed entries store a smaller size in the archive
+			toc_entry.offset = value2 as u64;
+			toc_entry.size_in_archive = value3;
+			toc_entry.size = value4;
+			toc_entry.is_compressed = value3 != value4;
+		}
+		
+		toc_entries.push(toc_entry);
+	}
+	
+	Ok(toc_entries)
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::io::Cursor;
+	
+	use super::super::ByteOrder;
+	
+	fn push_values(buffer: &mut Vec<u8>, values: &[u32])
+	{
+		for value in values
+		{
+			buffer.extend(&value.to_be_bytes());
+		}
+	}
+	
+	// a big endian archive, as on Xbox 360 and Wii, with a file in a sub directory
+	fn get_fixture() -> Vec<u8>
+	{
+		let mut data = Vec::new();
+		data.extend(b"RPF0");
+		push_values(&mut data, &[64, 3]);
+		data.resize(2048, 0);
+		push_values(&mut data, &[0x80000000, 1, 1, 0]);
+		push_values(&mut data, &[0x80000001, 2, 1, 0]);
+		push_values(&mut data, &[5, 4096, 4, 4]);
+		data.extend(b"\0a\0\0\0b.txt\0");
+		
+		data.resize(4096, 0);
+		data.extend(b"abcd");
+		data
+	}
+	
+	#[test]
+	fn big_endian_archives_are_parsed()
+	{
+		let mut format = crate::format::Format::default();
+		format.parse_from(Cursor::new(get_fixture())).unwrap();
+		
+		assert_eq!(format.rpf_byte_order, ByteOrder::Big);
+		assert_eq!(format.entries.len(), 1);
+		
+		let entry = format.entries[0].clone();
+		assert_eq!(entry.get_path(), "a/b.txt");
+		assert_eq!(format.get_entry_file_data(&entry).unwrap(), b"abcd");
+	}
+}
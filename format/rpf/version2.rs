@@ -0,0 +1,538 @@
+use std::convert::TryInto;
+use std::fs::File;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Read;
+use std::io::Write;
+use std::io::Seek;
+use std::io::SeekFrom;
+
+use crate::error::ImgError;
+
+use super::super::Format;
+use super::super::FormatType;
+use super::super::ReadSeek;
+use super::super::archive_format::ArchiveFormat;
+
+// format
+pub struct RpfVersion2
+{
+	pub encrypted: bool
+}
+
+impl ArchiveFormat for RpfVersion2
+{
+	fn get_format_type(&self) -> FormatType
+	{
+		FormatType::RPF
+	}
+	
+	fn get_version(&self) -> u8
+	{
+		2
+	}
+	
+	fn get_name(&self) -> &'static str
+	{
+		if self.encrypted
+		{
+			"RPF version 2 (Encrypted)"
+		}
+		else
+		{
+			"RPF version 2"
+		}
+	}
+	
+	fn is_encrypted(&self) -> bool
+	{
+		self.encrypted
+	}
+	
+	fn detect(&self, header: &[u8]) -> bool
+	{
+		if header.len() < 4 || &header[0..4] != b"RPF2"
+		{
+			return false;
+		}
+		
+		let encrypted = header.len() >= 20 && u32::from_le_bytes(header[16..20].try_into().unwrap()) != 0;
+		encrypted == self.encrypted
+	}
+	
+	fn parse(&self, format: &mut Format, mut reader: &mut dyn ReadSeek) -> Result<(), ImgError>
+	{
+		parse_list(format, &mut reader)
+	}
+	
+	fn save(&self, format: &mut Format, img_path_out: &str, _dir_path_out: &str) -> Result<(), ImgError>
+	{
+		save_list(format, img_path_out, 2, self.encrypted, crate::keystore::KEY_NAME_GTA_4)
+	}
+	
+	fn get_header_size(&self) -> u64
+	{
+		2048
+	}
+	
+	fn get_directory_entry_size(&self) -> u64
+	{
+		16
+	}
+	
+	// folder records and the name table
+	fn get_names_size(&self, format: &Format) -> u64
+	{
+		get_toc_size(&get_toc_items(format, false), 2) - (format.entries.len() as u64) * 16
+	}
+	
+	fn supports_resource_types(&self) -> bool
+	{
+		true
+	}
+	
+	fn supports_folders(&self) -> bool
+	{
+		true
+	}
+}
+
+// parse
+pub fn parse_list<R: Read + Seek>(format: &mut super::super::Format, reader: &mut R) -> Result<(), ImgError>
+{
+	let (mut buffer_toc, toc_entries) = read_toc(format, reader, crate::keystore::KEY_NAME_GTA_4)?;
+	
+	format.entries = Vec::new();
+	
+	if !toc_entries.is_empty()
+	{
+		let names = super::get_toc_names(&mut buffer_toc, &toc_entries, (toc_entries.len() as u64) * 16)?;
+		super::add_directory_entries(format, &toc_entries, &names, 0, String::from(""))?;
+	}
+	
+	Ok(())
+}
+
+// header and TOC, decrypted and parsed in the byte order that makes sense, shared with versions 3 and 4
+// encrypted TOCs use the GTA IV key, or the user-supplied key with the name given
+pub fn read_toc<R: Read + Seek>(format: &mut super::super::Format, reader: &mut R, key_name: &str) -> Result<(Vec<u8>, Vec<super::TocEntry>), ImgError>
+{
+	let file_size = crate::utility::get_reader_size(reader)?;
+	reader.seek(SeekFrom::Start(0))?;
+	
+	let mut buffer = [0; 20];
+	reader.read_exact(&mut buffer).map_err(ImgError::header)?;
+	
+	// the encrypted flag is 0 or not in either byte order
+	let encrypted = u32::from_le_bytes(buffer[16..20].try_into().unwrap()) != 0;
+	
+	for byte_order in super::BYTE_ORDERS.iter()
+	{
+		let table_data_size = byte_order.get_u32(&buffer, 4);
+		let entry_count = byte_order.get_u32(&buffer, 8);
+		
+		if crate::utility::check_directory_range(file_size, 2048, table_data_size as u64, 1).is_err() || (table_data_size as u64) < (entry_count as u64) * 16
+		{
+			continue;
+		}
+		
+		// read TOC
+		reader.seek(SeekFrom::Start(2048))?;
+		
+		let mut buffer_toc = vec![0; table_data_size as usize];
+		reader.read_exact(&mut buffer_toc).map_err(ImgError::directory)?;
+		
+		if encrypted
+		{
+			decrypt_toc(&mut buffer_toc, format.keys.get_key(key_name)?)?;
+		}
+		
+		let toc_entries = parse_toc_entries(&buffer_toc, entry_count, *byte_order)?;
+		if !super::is_root_valid(&toc_entries)
+		{
+			continue;
+		}
+		
+		format.rpf_byte_order = *byte_order;
+		format.rpf_header_unknown = byte_order.get_u32(&buffer, 12);
+		return Ok((buffer_toc, toc_entries));
+	}
+	
+	Err(ImgError::DirectoryOutOfRange)
+}
+
+// decrypt TOC in place with a key from the keystore, a remainder smaller than a block is not encrypted
+pub fn decrypt_toc(buffer_toc: &mut [u8], key: &Vec<u8>) -> Result<(), ImgError>
+{
+	crate::utility::decrypt_with_key(key, buffer_toc, 16)
+}
+
+pub fn parse_toc_entries(buffer: &Vec<u8>, entry_count: u32, byte_order: super::ByteOrder) -> Result<Vec<super::TocEntry>, ImgError>
+{
+	let mut toc_entries = Vec::with_capacity(entry_count as usize);
+	
+	for i in 0..(entry_count as usize)
+	{
+		let seek = i * 16;
+		let value1 = byte_order.get_u32(buffer, seek);
+		let value2 = byte_order.get_u32(buffer, seek + 4);
+		let value3 = byte_order.get_u32(buffer, seek + 8);
+		let value4 = byte_order.get_u32(buffer, seek + 12);
+		
+		let mut toc_entry = super::TocEntry
+		{
+			name_offset: value1,
+			is_directory: (value3 & 0x80000000) != 0,
+			size: 0,
+			offset: 0,
+			size_in_archive: 0,
+			is_compressed: false,
+			is_resource: false,
+			resource_type: 0,
+			resource_flags: 0,
+			resource_graphics_flags: 0,
+			encryption: 0,
+			flags: 0,
+			content_entry_index: 0,
+			content_entry_count: 0
+		};
+		
+		if toc_entry.is_directory
+		{
+			toc_entry.flags = value2;
+			toc_entry.content_entry_index = value3 & 0x7FFFFFFF;
+			toc_entry.content_entry_count = value4 & 0x3FFFFFFF;
+		}
+		else
+		{
+			toc_entry.size = value2;
+			toc_entry.is_resource = (value4 & 0xC0000000) == 0xC0000000;
+			
+			if toc_entry.is_resource
+			{
+				toc_entry.offset = (value3 & 0x7FFFFF00) as u64;
+				toc_entry.resource_type = value3 & 0xFF;
+				toc_entry.resource_flags = value4;
+				toc_entry.size_in_archive = value2;
+			}
+			else
+			{
+				toc_entry.offset = value3 as u64;
+				toc_entry.is_compressed = (value4 & 0x40000000) != 0;
+				toc_entry.size_in_archive = value4 & 0xBFFFFFFF;
+			}
+		}
+		
+		toc_entries.push(toc_entry);
+	}
+	
+	Ok(toc_entries)
+}
+
+// save
+pub struct TocItem
+{
+	pub name: String,
+	pub entry_index: Option<usize>,
+	
+	// directory
+	pub folder_path: String,
+	pub content_entry_index: u32,
+	pub content_entry_count: u32
+}
+
+// TOC records in file order, the root directory first and the contents of each directory after it
+pub fn get_toc_items(format: &Format, sort_by_name: bool) -> Vec<TocItem>
+{
+	// folders are grouped ignoring case, as lookups do, and keep the case of their first entry
+	let mut paths = Vec::with_capacity(format.entries.len());
+	for entry in format.entries.iter()
+	{
+		let (entry_folder_path, name) = super::super::entry_tree::split_path(&entry.get_path());
+		paths.push((entry_folder_path.to_ascii_lowercase(), entry_folder_path, name));
+	}
+	
+	let mut toc_items = vec![TocItem
+	{
+		name: String::from(""),
+		entry_index: None,
+		folder_path: String::from(""),
+		content_entry_index: 0,
+		content_entry_count: 0
+	}];
+	
+	let mut i = 0;
+	while i < toc_items.len()
+	{
+		if toc_items[i].entry_index.is_some()
+		{
+			i += 1;
+			continue;
+		}
+		
+		let folder_path = toc_items[i].folder_path.clone();
+		let folder_path_lower = folder_path.to_ascii_lowercase();
+		let start = toc_items.len();
+		
+		// sub directories
+		let mut sub_folder_names : Vec<String> = Vec::new();
+		for (entry_folder_path_lower, entry_folder_path, _name) in paths.iter()
+		{
+			if entry_folder_path_lower.len() > folder_path_lower.len() && entry_folder_path_lower.starts_with(&folder_path_lower)
+			{
+				let rest = &entry_folder_path[folder_path.len()..];
+				let sub_folder_name = rest[0..rest.find('/').unwrap()].to_string();
+				if !sub_folder_names.iter().any(|name| name.eq_ignore_ascii_case(&sub_folder_name))
+				{
+					sub_folder_names.push(sub_folder_name);
+				}
+			}
+		}
+		
+		for sub_folder_name in sub_folder_names
+		{
+			toc_items.push(TocItem
+			{
+				folder_path: format!("{}{}/", folder_path, sub_folder_name),
+				name: sub_folder_name,
+				entry_index: None,
+				content_entry_index: 0,
+				content_entry_count: 0
+			});
+		}
+		
+		// files
+		for (entry_index, (entry_folder_path_lower, _entry_folder_path, name)) in paths.iter().enumerate()
+		{
+			if *entry_folder_path_lower == folder_path_lower
+			{
+				toc_items.push(TocItem
+				{
+					name: name.clone(),
+					entry_index: Some(entry_index),
+					folder_path: String::from(""),
+					content_entry_index: 0,
+					content_entry_count: 0
+				});
+			}
+		}
+		
+		// directories and files mixed, for archives that look names up with a binary search
+		if sort_by_name
+		{
+			toc_items[start..].sort_by_key(|toc_item| toc_item.name.to_lowercase());
+		}
+		
+		toc_items[i].content_entry_index = start as u32;
+		toc_items[i].content_entry_count = (toc_items.len() - start) as u32;
+		
+		i += 1;
+	}
+	
+	toc_items
+}
+
+// TOC records and name table, padded to the AES block size, version 3 has name hashes instead of a name table
+pub fn get_toc_size(toc_items: &Vec<TocItem>, version: u8) -> u64
+{
+	let mut names_size = 0;
+	if version != 3
+	{
+		for toc_item in toc_items.iter()
+		{
+			names_size += crate::name_codec::get_encoded_len(&toc_item.name) + 1;
+		}
+	}
+	
+	let toc_size = (toc_items.len() * 16) + names_size;
+	(toc_size.div_ceil(16) * 16) as u64
+}
+
+pub fn save_list(format: &mut super::super::Format, img_path_out: &str, version: u8, encrypted: bool, key_name: &str) -> Result<(), ImgError>
+{
+	let img_uses_temp_path_out : bool = format.img_path_in == img_path_out;
+	
+	let img_temp_path_out : String = crate::utility::get_next_file_path2(format.get_saving_dir()?, "temp.rpf".to_string());
+	let img_path_out2 = if img_uses_temp_path_out
+	{
+		img_temp_path_out
+	}
+	else
+	{
+		img_path_out.to_string()
+	};
+	
+	let toc_items = get_toc_items(format, false);
+	let toc_size = get_toc_size(&toc_items, version);
+	let byte_order = format.rpf_byte_order;
+	
+	// entry layout, entries keep their offset unless it overlaps the TOC or the previous entry
+	let mut sizes : Vec<u64> = Vec::with_capacity(format.entries.len());
+	for entry in format.entries.iter()
+	{
+		if entry.data_temp_path.is_empty()
+		{
+			sizes.push(entry.size);
+		}
+		else
+		{
+			sizes.push(crate::utility::get_file_size(entry.data_temp_path.clone()));
+		}
+	}
+	
+	let mut seek = crate::utility::to_sector_bytes(2048 + toc_size);
+	
+	let mut entry_indices : Vec<usize> = (0..format.entries.len()).collect();
+	entry_indices.sort_by_key(|entry_index| format.entries[*entry_index].offset_out);
+	
+	for entry_index in entry_indices
+	{
+		let mut offset = format.entries[entry_index].offset_out;
+		if offset < seek || offset % 2048 != 0
+		{
+			offset = seek;
+		}
+		
+		format.entries[entry_index].offset_out = offset;
+		seek = crate::utility::to_sector_bytes(offset + sizes[entry_index]);
+	}
+	
+	// TOC
+	let mut buffer_names = Vec::new();
+	let mut buffer_toc = Vec::with_capacity(toc_size as usize);
+	
+	for toc_item in toc_items.iter()
+	{
+		let name_offset = if version == 3
+		{
+			super::version3::get_name_hash(&toc_item.name)
+		}
+		else
+		{
+			let name_offset = buffer_names.len() as u32;
+			buffer_names.extend(crate::name_codec::encode(&toc_item.name)?);
+			buffer_names.push(0);
+			name_offset
+		};
+		
+		let (value2, value3, value4) = match toc_item.entry_index
+		{
+			None => (0, 0x80000000 | toc_item.content_entry_index, toc_item.content_entry_count),
+			Some(entry_index) =>
+			{
+				let entry = &format.entries[entry_index];
+				let size = crate::utility::to_u32_field(sizes[entry_index], "entry size")?;
+				let offset = crate::utility::to_u32_field(entry.offset_out, "entry offset")?;
+				let rpf = match &entry.rpf
+				{
+					Some(rpf) => rpf.clone(),
+					None => super::super::entry::RpfEntry
+					{
+						uncompressed_size: size,
+						..Default::default()
+					}
+				};
+				
+				if rpf.is_resource
+				{
+					(size, offset | (entry.resource_type & 0xFF), rpf.resource_flags | 0xC0000000)
+				}
+				else if rpf.is_compressed
+				{
+					(rpf.uncompressed_size, offset, size | 0x40000000)
+				}
+				else
+				{
+					(size, offset, size)
+				}
+			}
+		};
+		
+		buffer_toc.extend(&byte_order.to_bytes(name_offset));
+		buffer_toc.extend(&byte_order.to_bytes(value2));
+		buffer_toc.extend(&byte_order.to_bytes(value3));
+		buffer_toc.extend(&byte_order.to_bytes(value4));
+	}
+	
+	buffer_toc.extend(buffer_names);
+	buffer_toc.resize(toc_size as usize, 0);
+	
+	if encrypted
+	{
+		crate::utility::encrypt_gta_4(format.keys.get_key(key_name)?, &mut buffer_toc)?;
+	}
+	
+	let file_out = File::create(&img_path_out2)?;
+	let mut buffer_out = BufWriter::new(file_out);
+	
+	// header
+	let mut buffer = Vec::with_capacity(2048);
+	buffer.extend(format!("RPF{}", version).as_bytes());
+	buffer.extend(&byte_order.to_bytes(toc_size as u32));
+	buffer.extend(&byte_order.to_bytes(toc_items.len() as u32));
+	buffer.extend(&byte_order.to_bytes(format.rpf_header_unknown));
+	buffer.extend(&byte_order.to_bytes(if encrypted { 1 } else { 0 }));
+	buffer.resize(2048, 0);
+	
+	buffer.extend(buffer_toc);
+	let remainder2 = buffer.len() % 2048;
+	if remainder2 != 0
+	{
+		crate::utility::write_zeros(&mut buffer, (2048 - remainder2) as u64);
+	}
+	
+	let mut seek = buffer.len() as u64;
+	buffer_out.write_all(buffer.as_slice())?;
+	
+	// entry data
+	let img_is_new = format.is_new();
+	
+	let mut reader : Option<BufReader<File>> = None;
+	
+	if !img_is_new
+	{
+		reader = Some(BufReader::new(File::open(&format.img_path_in)?));
+	}
+	
+	let entries : Vec<super::super::entry::Entry> = format.get_entries_sorted_by_offset_out();
+	for entry in entries
+	{
+		// pad entry gaps
+		let entry_offset = entry.offset_out;
+		if seek < entry_offset
+		{
+			let diff = entry_offset - seek;
+			crate::utility::write_zeros_to(&mut buffer_out, diff)?;
+			seek += diff;
+		}
+		
+		// copy entry data
+		let data_size = if img_is_new
+		{
+			format.copy_entry_data(&entry, &mut buffer_out)?
+		}
+		else
+		{
+			entry.copy_data_with_reader(reader.as_mut().unwrap(), &mut buffer_out)?
+		};
+		seek += data_size;
+		
+		// pad entry data
+		if seek % 2048 != 0
+		{
+			let remainder = 2048 - (seek % 2048);
+			crate::utility::write_zeros_to(&mut buffer_out, remainder)?;
+			seek += remainder;
+		}
+	}
+	
+	buffer_out.flush()?;
+	drop(buffer_out);
+	
+	if img_uses_temp_path_out
+	{
+		crate::utility::move_file_over(&img_path_out2, img_path_out)?;
+	}
+	
+	Ok(())
+}
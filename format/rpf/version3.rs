@@ -0,0 +1,211 @@
+use std::convert::TryInto;
+use std::io::Read;
+use std::io::Seek;
+
+use crate::error::ImgError;
+
+use super::super::Format;
+use super::super::FormatType;
+use super::super::ReadSeek;
+use super::super::archive_format::ArchiveFormat;
+
+// format
+pub struct RpfVersion3
+{
+	pub encrypted: bool
+}
+
+impl ArchiveFormat for RpfVersion3
+{
+	fn get_format_type(&self) -> FormatType
+	{
+		FormatType::RPF
+	}
+	
+	fn get_version(&self) -> u8
+	{
+		3
+	}
+	
+	fn get_name(&self) -> &'static str
+	{
+		if self.encrypted
+		{
+			"RPF version 3 (Encrypted)"
+		}
+		else
+		{
+			"RPF version 3"
+		}
+	}
+	
+	fn is_encrypted(&self) -> bool
+	{
+		self.encrypted
+	}
+	
+	fn detect(&self, header: &[u8]) -> bool
+	{
+		if header.len() < 4 || &header[0..4] != b"RPF3"
+		{
+			return false;
+		}
+		
+		let encrypted = header.len() >= 20 && u32::from_le_bytes(header[16..20].try_into().unwrap()) != 0;
+		encrypted == self.encrypted
+	}
+	
+	fn parse(&self, format: &mut Format, mut reader: &mut dyn ReadSeek) -> Result<(), ImgError>
+	{
+		parse_list(format, &mut reader)
+	}
+	
+	fn save(&self, format: &mut Format, img_path_out: &str, _dir_path_out: &str) -> Result<(), ImgError>
+	{
+		super::version2::save_list(format, img_path_out, 3, self.encrypted, crate::keystore::KEY_NAME_MCLA)
+	}
+	
+	fn get_header_size(&self) -> u64
+	{
+		2048
+	}
+	
+	fn get_directory_entry_size(&self) -> u64
+	{
+		16
+	}
+	
+	// folder records
+	fn get_names_size(&self, format: &Format) -> u64
+	{
+		super::version2::get_toc_size(&super::version2::get_toc_items(format, false), 3) - (format.entries.len() as u64) * 16
+	}
+	
+	fn supports_resource_types(&self) -> bool
+	{
+		true
+	}
+	
+	fn supports_folders(&self) -> bool
+	{
+		true
+	}
+}
+
+// parse, the same TOC as version 2 with name hashes instead of name offsets
+pub fn parse_list<R: Read + Seek>(format: &mut super::super::Format, reader: &mut R) -> Result<(), ImgError>
+{
+	let (_, toc_entries) = super::version2::read_toc(format, reader, crate::keystore::KEY_NAME_MCLA)?;
+	
+	format.entries = Vec::new();
+	
+	if !toc_entries.is_empty()
+	{
+		let mut names = Vec::with_capacity(toc_entries.len());
+		for toc_entry in toc_entries.iter()
+		{
+			names.push(get_name(format, toc_entry.name_offset));
+		}
+		
+		// the root directory has no name
+		names[0] = String::from("");
+		
+		super::add_directory_entries(format, &toc_entries, &names, 0, String::from(""))?;
+	}
+	
+	Ok(())
+}
+
+// name for a hash, or a placeholder for hashes not in the name list
+pub fn get_name(format: &super::super::Format, name_hash: u32) -> String
+{
+	match format.hash_names.get(&name_hash)
+	{
+		Some(name) => name.clone(),
+		None => format!("0x{:08X}", name_hash)
+	}
+}
+
+// hash for a name, placeholder names keep their hash
+pub fn get_name_hash(name: &str) -> u32
+{
+	if name.len() == 10 && name.starts_with("0x")
+	{
+		if let Ok(name_hash) = u32::from_str_radix(&name[2..], 16)
+		{
+			return name_hash;
+		}
+	}
+	crate::utility::get_jenkins_hash(name)
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::io::Cursor;
+	
+	use super::super::ByteOrder;
+	
+	fn push_values(buffer: &mut Vec<u8>, values: &[u32])
+	{
+		for value in values
+		{
+			buffer.extend(&value.to_be_bytes());
+		}
+	}
+	
+	// a big endian archive, as on Xbox 360 and PS3, with two files in the root directory
+	fn get_fixture() -> Vec<u8>
+	{
+		let mut data = Vec::new();
+		data.extend(b"RPF3");
+		push_values(&mut data, &[48, 3, 0x1234, 0]);
+		data.resize(2048, 0);
+		push_values(&mut data, &[0, 0, 0x80000001, 2]);
+		push_values(&mut data, &[crate::utility::get_jenkins_hash("a.txt"), 4, 4096, 4]);
+		push_values(&mut data, &[0x12345678, 4, 6144, 4]);
+		
+		data.resize(4096, 0);
+		data.extend(b"abcd");
+		data.resize(6144, 0);
+		data.extend(b"efgh");
+		data
+	}
+	
+	fn check_entries(format: &mut crate::format::Format)
+	{
+		assert_eq!(format.rpf_byte_order, ByteOrder::Big);
+		assert_eq!(format.rpf_header_unknown, 0x1234);
+		
+		let names : Vec<String> = format.entries.iter().map(|entry| entry.get_path()).collect();
+		assert_eq!(names, vec!["a.txt", "0x12345678"]);
+		
+		let entry = format.entries[0].clone();
+		assert_eq!(format.get_entry_file_data(&entry).unwrap(), b"abcd");
+		let entry = format.entries[1].clone();
+		assert_eq!(format.get_entry_file_data(&entry).unwrap(), b"efgh");
+	}
+	
+	#[test]
+	fn big_endian_archives_resolve_hashes_and_are_saved_big_endian()
+	{
+		let mut format = crate::format::Format::default();
+		format.hash_names.insert(crate::utility::get_jenkins_hash("a.txt"), String::from("a.txt"));
+		format.parse_from(Cursor::new(get_fixture())).unwrap();
+		check_entries(&mut format);
+		
+		let path = std::env::temp_dir().join("imgdesk_test_rpf3_big_endian.rpf").to_string_lossy().to_string();
+		format.save(&path, "").unwrap();
+		
+		let mut format2 = crate::format::Format
+		{
+			hash_names: format.hash_names.clone(),
+			..Default::default()
+		};
+		format2.parse(&path, "").unwrap();
+		check_entries(&mut format2);
+		
+		format2.reset();
+		std::fs::remove_file(&path).unwrap();
+	}
+}
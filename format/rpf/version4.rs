@@ -0,0 +1,159 @@
+use std::io::Read;
+use std::io::Seek;
+
+use crate::error::ImgError;
+
+use super::super::Format;
+use super::super::FormatType;
+use super::super::ReadSeek;
+use super::super::archive_format::ArchiveFormat;
+
+// format
+pub struct RpfVersion4;
+
+impl ArchiveFormat for RpfVersion4
+{
+	fn get_format_type(&self) -> FormatType
+	{
+		FormatType::RPF
+	}
+	
+	fn get_version(&self) -> u8
+	{
+		4
+	}
+	
+	fn get_name(&self) -> &'static str
+	{
+		"RPF version 4"
+	}
+	
+	fn detect(&self, header: &[u8]) -> bool
+	{
+		header.len() >= 4 && &header[0..4] == b"RPF4"
+	}
+	
+	fn parse(&self, format: &mut Format, mut reader: &mut dyn ReadSeek) -> Result<(), ImgError>
+	{
+		parse_list(format, &mut reader)
+	}
+	
+	fn save(&self, _format: &mut Format, _img_path_out: &str, _dir_path_out: &str) -> Result<(), ImgError>
+	{
+		Err(ImgError::Unsupported(String::from("saving RPF version 4")))
+	}
+	
+	fn get_header_size(&self) -> u64
+	{
+		2048
+	}
+	
+	fn get_directory_entry_size(&self) -> u64
+	{
+		16
+	}
+	
+	fn supports_resource_types(&self) -> bool
+	{
+		true
+	}
+	
+	fn supports_folders(&self) -> bool
+	{
+		true
+	}
+}
+
+// parse, the same header and TOC records as version 2, with the TOC encrypted by a user-supplied key
+pub fn parse_list<R: Read + Seek>(format: &mut super::super::Format, reader: &mut R) -> Result<(), ImgError>
+{
+	let (mut buffer_toc, toc_entries) = super::version2::read_toc(format, reader, crate::keystore::KEY_NAME_MP3)?;
+	
+	format.entries = Vec::new();
+	
+	if !toc_entries.is_empty()
+	{
+		let names = super::get_toc_names(&mut buffer_toc, &toc_entries, (toc_entries.len() as u64) * 16)?;
+		super::add_directory_entries(format, &toc_entries, &names, 0, String::from(""))?;
+	}
+	
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::io::Cursor;
+	
+	use crate::error::ImgError;
+	
+	use crate::keystore::KEY_NAME_MP3;
+	
+	fn push_values(buffer: &mut Vec<u8>, values: &[u32])
+	{
+		for value in values
+		{
+			buffer.extend(&value.to_le_bytes());
+		}
+	}
+	
+	// any key will do, the TOC is encrypted here with the same one
+	fn get_key() -> Vec<u8>
+	{
+		(0..32).collect()
+	}
+	
+	// a little endian archive with an encrypted TOC, two files in the root directory and one in a sub folder
+	fn get_fixture() -> Vec<u8>
+	{
+		let mut toc = Vec::new();
+		push_values(&mut toc, &[0, 0, 0x80000001, 3]);
+		push_values(&mut toc, &[1, 4, 4096, 4]);
+		push_values(&mut toc, &[7, 0, 0x80000004, 1]);
+		push_values(&mut toc, &[9, 4, 6144, 4]);
+		push_values(&mut toc, &[15, 4, 8192, 4]);
+		toc.extend(b"\0a.txt\0b\0c.txt\0d.txt\0");
+		toc.resize(112, 0);
+		crate::utility::AesEcb::new(&get_key(), 16).unwrap().encrypt(&mut toc);
+		
+		let mut data = Vec::new();
+		data.extend(b"RPF4");
+		push_values(&mut data, &[toc.len() as u32, 5, 0, 1]);
+		data.resize(2048, 0);
+		data.extend(&toc);
+		
+		data.resize(4096, 0);
+		data.extend(b"abcd");
+		data.resize(6144, 0);
+		data.extend(b"efgh");
+		data.resize(8192, 0);
+		data.extend(b"ijkl");
+		data
+	}
+	
+	#[test]
+	fn encrypted_archives_are_parsed_with_the_rpf4_key()
+	{
+		let mut format = crate::format::Format::default();
+		format.keys.add(KEY_NAME_MP3, get_key());
+		format.parse_from(Cursor::new(get_fixture())).unwrap();
+		
+		assert_eq!((format.format_type, format.img_version, format.img_encrypted), (crate::format::FormatType::RPF, 4, false));
+		let paths : Vec<String> = format.entries.iter().map(|entry| entry.get_path()).collect();
+		assert_eq!(paths, vec!["a.txt", "b/d.txt", "c.txt"]);
+		for (entry, data) in format.entries.clone().iter().zip([b"abcd", b"ijkl", b"efgh"].iter())
+		{
+			assert_eq!(&format.get_entry_file_data(entry).unwrap(), data);
+		}
+		format.reset();
+	}
+	
+	#[test]
+	fn encrypted_archives_need_the_rpf4_key()
+	{
+		let mut format = crate::format::Format::default();
+		let result = format.parse_from(Cursor::new(get_fixture()));
+		assert!(matches!(result, Err(ImgError::MissingKey(name)) if name == KEY_NAME_MP3));
+		format.reset();
+	}
+}
@@ -0,0 +1,273 @@
+use std::convert::TryInto;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+
+use crate::error::ImgError;
+
+use super::super::Format;
+use super::super::FormatType;
+use super::super::ReadSeek;
+use super::super::archive_format::ArchiveFormat;
+use super::super::entry::RpfEntry;
+
+// format
+pub struct RpfVersion6;
+
+impl ArchiveFormat for RpfVersion6
+{
+	fn get_format_type(&self) -> FormatType
+	{
+		FormatType::RPF
+	}
+	
+	fn get_version(&self) -> u8
+	{
+		6
+	}
+	
+	fn get_name(&self) -> &'static str
+	{
+		"RPF version 6"
+	}
+	
+	fn detect(&self, header: &[u8]) -> bool
+	{
+		header.len() >= 4 && &header[0..4] == b"RPF6"
+	}
+	
+	fn parse(&self, format: &mut Format, mut reader: &mut dyn ReadSeek) -> Result<(), ImgError>
+	{
+		parse_list(format, &mut reader)
+	}
+	
+	fn save(&self, _format: &mut Format, _img_path_out: &str, _dir_path_out: &str) -> Result<(), ImgError>
+	{
+		Err(ImgError::Unsupported(String::from("saving RPF version 6")))
+	}
+	
+	fn get_header_size(&self) -> u64
+	{
+		16
+	}
+	
+	fn get_directory_entry_size(&self) -> u64
+	{
+		20
+	}
+	
+	fn supports_resource_types(&self) -> bool
+	{
+		true
+	}
+	
+	fn supports_folders(&self) -> bool
+	{
+		true
+	}
+	
+	// RSC85, in the byte order of the archive and with the graphics flags
+	fn get_resource_header(&self, format: &Format, rpf: &RpfEntry, resource_type: u32) -> Vec<u8>
+	{
+		let byte_order = format.rpf_byte_order;
+		let mut header = Vec::with_capacity(16);
+		header.extend(b"RSC\x85");
+		header.extend(&byte_order.to_bytes(resource_type));
+		header.extend(&byte_order.to_bytes(rpf.resource_flags));
+		header.extend(&byte_order.to_bytes(rpf.resource_graphics_flags));
+		header
+	}
+}
+
+// parse
+pub fn parse_list<R: Read + Seek>(format: &mut super::super::Format, reader: &mut R) -> Result<(), ImgError>
+{
+	let file_size = crate::utility::get_reader_size(reader)?;
+	reader.seek(SeekFrom::Start(0))?;
+	
+	let mut buffer = [0; 16];
+	reader.read_exact(&mut buffer).map_err(ImgError::header)?;
+	
+	// the encrypted flag is 0 or not in either byte order
+	let encrypted = u32::from_le_bytes(buffer[12..16].try_into().unwrap()) != 0;
+	
+	for byte_order in super::BYTE_ORDERS.iter()
+	{
+		let entry_count = byte_order.get_u32(&buffer, 4);
+		if crate::utility::check_directory_range(file_size, 16, entry_count as u64, 20).is_err()
+		{
+			continue;
+		}
+		
+		// read TOC, straight after the header
+		reader.seek(SeekFrom::Start(16))?;
+		
+		let mut buffer_toc = vec![0; (entry_count as usize) * 20];
+		reader.read_exact(&mut buffer_toc).map_err(ImgError::directory)?;
+		
+		if encrypted
+		{
+			super::version2::decrypt_toc(&mut buffer_toc, format.keys.get_key(crate::keystore::KEY_NAME_RDR)?)?;
+		}
+		
+		// parse TOC
+		let toc_entries = parse_toc_entries(&buffer_toc, entry_count, *byte_order)?;
+		if !super::is_root_valid(&toc_entries)
+		{
+			continue;
+		}
+		
+		format.rpf_byte_order = *byte_order;
+		format.entries = Vec::new();
+		
+		if !toc_entries.is_empty()
+		{
+			let mut names = Vec::with_capacity(toc_entries.len());
+			for toc_entry in toc_entries.iter()
+			{
+				names.push(super::version3::get_name(format, toc_entry.name_offset));
+			}
+			
+			// the root directory has no name
+			names[0] = String::from("");
+			
+			super::add_directory_entries(format, &toc_entries, &names, 0, String::from(""))?;
+		}
+		
+		return Ok(());
+	}
+	
+	Err(ImgError::DirectoryOutOfRange)
+}
+
+// 20 byte records: name hash, size in archive, offset in 8 byte blocks or the directory content index, then sizes or flags
+pub fn parse_toc_entries(buffer: &Vec<u8>, entry_count: u32, byte_order: super::ByteOrder) -> Result<Vec<super::TocEntry>, ImgError>
+{
+	let mut toc_entries = Vec::with_capacity(entry_count as usize);
+	
+	for i in 0..(entry_count as usize)
+	{
+		let seek = i * 20;
+		let value1 = byte_order.get_u32(buffer, seek);
+		let value2 = byte_order.get_u32(buffer, seek + 4);
+		let value3 = byte_order.get_u32(buffer, seek + 8);
+		let value4 = byte_order.get_u32(buffer, seek + 12);
+		let value5 = byte_order.get_u32(buffer, seek + 16);
+		
+		let mut toc_entry = super::TocEntry
+		{
+			name_offset: value1,
+			is_directory: (value3 & 0x80000000) != 0,
+			size: 0,
+			offset: 0,
+			size_in_archive: 0,
+			is_compressed: false,
+			is_resource: false,
+			resource_type: 0,
+			resource_flags: 0,
+			resource_graphics_flags: 0,
+			encryption: 0,
+			flags: 0,
+			content_entry_index: 0,
+			content_entry_count: 0
+		};
+		
+		if toc_entry.is_directory
+		{
+			toc_entry.content_entry_index = value3 & 0x7FFFFFFF;
+			toc_entry.content_entry_count = value4;
+		}
+		else
+		{
+			toc_entry.size_in_archive = value2;
+			
+			// resources are always compressed, and store their type in the low byte of the offset and their RSC flags instead of the uncompressed size
+			toc_entry.is_resource = (value4 & 0x80000000) != 0;
+			let offset_blocks = if toc_entry.is_resource { value3 & 0x7FFFFF00 } else { value3 & 0x7FFFFFFF };
+			toc_entry.offset = (offset_blocks as u64) << 3;
+			
+			if toc_entry.is_resource
+			{
+				toc_entry.resource_type = value3 & 0xFF;
+				toc_entry.is_compressed = true;
+				toc_entry.resource_flags = value4 & 0x7FFFFFFF;
+				toc_entry.resource_graphics_flags = value5;
+				
+				let size = crate::format::resource::get_virtual_size(toc_entry.resource_flags) + crate::format::resource::get_physical_size(toc_entry.resource_flags);
+				toc_entry.size = size.min(u32::MAX as u64) as u32;
+			}
+			else
+			{
+				toc_entry.is_compressed = (value4 & 0x40000000) != 0;
+				toc_entry.size = value4 & 0x3FFFFFFF;
+			}
+		}
+		
+		toc_entries.push(toc_entry);
+	}
+	
+	Ok(toc_entries)
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::io::Cursor;
+	
+	use super::super::ByteOrder;
+	
+	fn push_values(buffer: &mut Vec<u8>, values: &[u32])
+	{
+		for value in values
+		{
+			buffer.extend(&value.to_be_bytes());
+		}
+	}
+	
+	// a big endian archive, as on Xbox 360 and PS3, with a file and a compressed resource in the root directory
+	fn get_fixture(resource_data: &Vec<u8>) -> Vec<u8>
+	{
+		let resource_data = crate::utility::compress_deflate(resource_data).unwrap();
+		
+		let mut data = Vec::new();
+		data.extend(b"RPF6");
+		push_values(&mut data, &[3, 0, 0]);
+		push_values(&mut data, &[0, 0, 0x80000001, 2, 0]);
+		push_values(&mut data, &[crate::utility::get_jenkins_hash("a.txt"), 4, 2048 >> 3, 4, 0]);
+		push_values(&mut data, &[0x12345678, resource_data.len() as u32, (4096 >> 3) | 0x01, 0x80000001, 0x20]);
+		
+		data.resize(2048, 0);
+		data.extend(b"abcd");
+		data.resize(4096, 0);
+		data.extend(&resource_data);
+		data
+	}
+	
+	#[test]
+	fn big_endian_archives_are_parsed_and_resources_get_a_header()
+	{
+		let resource_data = vec![7; 256];
+		
+		let mut format = crate::format::Format::default();
+		format.hash_names.insert(crate::utility::get_jenkins_hash("a.txt"), String::from("a.txt"));
+		format.parse_from(Cursor::new(get_fixture(&resource_data))).unwrap();
+		
+		assert_eq!(format.rpf_byte_order, ByteOrder::Big);
+		assert_eq!(format.entries.len(), 2);
+		
+		let entry = format.entries[0].clone();
+		assert_eq!(entry.get_path(), "a.txt");
+		assert_eq!(format.get_entry_file_data(&entry).unwrap(), b"abcd");
+		
+		let entry = format.entries[1].clone();
+		assert_eq!(entry.get_path(), "0x12345678");
+		assert_eq!(entry.offset_in, 4096);
+		assert_eq!(entry.resource_type, 1);
+		assert_eq!(entry.rpf.as_ref().unwrap().uncompressed_size, 256);
+		
+		let data = format.get_entry_file_data(&entry).unwrap();
+		assert_eq!(&data[0..4], b"RSC\x85");
+		assert_eq!(&data[4..16], &[0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 0x20]);
+		assert_eq!(&data[16..], resource_data.as_slice());
+	}
+}
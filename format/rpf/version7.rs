@@ -0,0 +1,682 @@
+use std::convert::TryInto;
+use std::fs::File;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+
+use crate::error::ImgError;
+use crate::keystore;
+use crate::keystore::Keystore;
+
+use super::super::Format;
+use super::super::FormatType;
+use super::super::ReadSeek;
+use super::super::archive_format::ArchiveFormat;
+
+// encryption types, from the header
+pub const ENCRYPTION_NONE : u32 = 0;
+pub const ENCRYPTION_OPEN : u32 = 0x4E45504F;
+pub const ENCRYPTION_AES : u32 = 0x0FFFFFF9;
+pub const ENCRYPTION_NG : u32 = 0x0FEFFFFF;
+
+// resources of this size or more store their size in these RSC7 header bytes, lowest byte first, and 0xFFFFFF in the TOC
+pub const LARGE_RESOURCE_SIZE : u64 = 0xFFFFFF;
+const LARGE_RESOURCE_SIZE_SEEKS : [usize; 4] = [7, 14, 5, 2];
+
+// format
+pub struct RpfVersion7;
+
+impl ArchiveFormat for RpfVersion7
+{
+	fn get_format_type(&self) -> FormatType
+	{
+		FormatType::RPF
+	}
+	
+	fn get_version(&self) -> u8
+	{
+		7
+	}
+	
+	fn get_name(&self) -> &'static str
+	{
+		"RPF version 7"
+	}
+	
+	fn detect(&self, header: &[u8]) -> bool
+	{
+		// the magic is stored as a little endian "RPF7"
+		header.len() >= 4 && &header[0..4] == b"7FPR"
+	}
+	
+	fn parse(&self, format: &mut Format, mut reader: &mut dyn ReadSeek) -> Result<(), ImgError>
+	{
+		parse_list(format, &mut reader)
+	}
+	
+	fn save(&self, format: &mut Format, img_path_out: &str, _dir_path_out: &str) -> Result<(), ImgError>
+	{
+		save_list(format, img_path_out)
+	}
+	
+	fn get_header_size(&self) -> u64
+	{
+		16
+	}
+	
+	fn get_directory_entry_size(&self) -> u64
+	{
+		16
+	}
+	
+	fn supports_resource_types(&self) -> bool
+	{
+		true
+	}
+	
+	fn supports_folders(&self) -> bool
+	{
+		true
+	}
+}
+
+// parse
+pub fn parse_list<R: Read + Seek>(format: &mut super::super::Format, reader: &mut R) -> Result<(), ImgError>
+{
+	let file_size = crate::utility::get_reader_size(reader)?;
+	reader.seek(SeekFrom::Start(0))?;
+	
+	let mut buffer = [0; 16];
+	reader.read_exact(&mut buffer).map_err(ImgError::header)?;
+	
+	let entry_count = u32::from_le_bytes(buffer[4..8].try_into().unwrap());
+	let names_length = u32::from_le_bytes(buffer[8..12].try_into().unwrap());
+	let encryption = u32::from_le_bytes(buffer[12..16].try_into().unwrap());
+	
+	let toc_size = (entry_count as u64) * 16 + (names_length as u64);
+	crate::utility::check_directory_range(file_size, 16, toc_size, 1)?;
+	
+	// read entry table and name table, straight after the header
+	let mut buffer_toc = vec![0; toc_size as usize];
+	reader.read_exact(&mut buffer_toc).map_err(ImgError::directory)?;
+	
+	// decrypt entry table and name table
+	match encryption
+	{
+		ENCRYPTION_NONE | ENCRYPTION_OPEN => {},
+		ENCRYPTION_AES | ENCRYPTION_NG =>
+		{
+			let archive_name = if format.ng_name.is_empty()
+			{
+				crate::utility::get_file_name(&format.img_path_in).unwrap_or("").to_string()
+			}
+			else
+			{
+				format.ng_name.clone()
+			};
+			if encryption == ENCRYPTION_NG && archive_name.is_empty()
+			{
+				return Err(ImgError::Unsupported(String::from("NG encrypted RPF version 7 without a file name")));
+			}
+			buffer_toc = decrypt(&format.keys, encryption, buffer_toc, &archive_name, file_size as u32)?;
+		},
+		_ => return Err(ImgError::Unsupported(format!("RPF version 7 encryption {:08X}", encryption)))
+	}
+	
+	// parse TOC
+	let mut toc_entries = parse_toc_entries(&buffer_toc, entry_count)?;
+	
+	format.entries = Vec::new();
+	
+	if !toc_entries.is_empty()
+	{
+		let names = super::get_toc_names(&mut buffer_toc, &toc_entries, (entry_count as u64) * 16)?;
+		
+		for i in 0..toc_entries.len()
+		{
+			let toc_entry = &mut toc_entries[i];
+			if toc_entry.is_directory
+			{
+				continue;
+			}
+			
+			// only binary entries with the flag set and scripts are encrypted, and only in AES or NG archives
+			let is_encrypted = if toc_entry.is_resource
+			{
+				names[i].to_lowercase().ends_with(".ysc")
+			}
+			else
+			{
+				toc_entry.encryption != 0
+			};
+			toc_entry.encryption = if is_encrypted && (encryption == ENCRYPTION_AES || encryption == ENCRYPTION_NG)
+			{
+				encryption
+			}
+			else
+			{
+				ENCRYPTION_NONE
+			};
+			
+			// large resources store their size in the RSC7 header
+			if toc_entry.is_resource && toc_entry.size_in_archive as u64 == LARGE_RESOURCE_SIZE
+			{
+				let mut buffer_rsc = [0; 16];
+				reader.seek(SeekFrom::Start(toc_entry.offset))?;
+				reader.read_exact(&mut buffer_rsc).map_err(ImgError::directory)?;
+				toc_entry.size_in_archive = get_large_resource_size(&buffer_rsc);
+			}
+		}
+		
+		super::add_directory_entries(format, &toc_entries, &names, 0, String::from(""))?;
+	}
+	
+	Ok(())
+}
+
+// 16 byte records, directories have 0x7FFFFF00 as their second u32
+pub fn parse_toc_entries(buffer: &Vec<u8>, entry_count: u32) -> Result<Vec<super::TocEntry>, ImgError>
+{
+	let mut toc_entries = Vec::with_capacity(entry_count as usize);
+	
+	for i in 0..(entry_count as usize)
+	{
+		let seek = i * 16;
+		let value1 = u32::from_le_bytes(buffer[seek..seek+4].try_into().unwrap());
+		let value2 = u32::from_le_bytes(buffer[seek+4..seek+8].try_into().unwrap());
+		let value3 = u32::from_le_bytes(buffer[seek+8..seek+12].try_into().unwrap());
+		let value4 = u32::from_le_bytes(buffer[seek+12..seek+16].try_into().unwrap());
+		
+		let mut toc_entry = super::TocEntry
+		{
+			name_offset: 0,
+			is_directory: value2 == 0x7FFFFF00,
+			size: 0,
+			offset: 0,
+			size_in_archive: 0,
+			is_compressed: false,
+			is_resource: false,
+			resource_type: 0,
+			resource_flags: 0,
+			resource_graphics_flags: 0,
+			encryption: 0,
+			flags: 0,
+			content_entry_index: 0,
+			content_entry_count: 0
+		};
+		
+		if toc_entry.is_directory
+		{
+			toc_entry.name_offset = value1;
+			toc_entry.content_entry_index = value3;
+			toc_entry.content_entry_count = value4;
+		}
+		else
+		{
+			// name offset: 16 bits, size in archive: 24 bits, offset in 512 byte blocks: 23 bits, resource flag: 1 bit
+			let value = (value1 as u64) | ((value2 as u64) << 32);
+			let size = ((value >> 16) & 0xFFFFFF) as u32;
+			
+			toc_entry.name_offset = (value & 0xFFFF) as u32;
+			toc_entry.offset = ((value >> 40) & 0x7FFFFF) * 512;
+			toc_entry.is_resource = (value >> 63) != 0;
+			
+			if toc_entry.is_resource
+			{
+				toc_entry.size_in_archive = size;
+				toc_entry.resource_type = (((value3 >> 28) & 0xF) << 4) | ((value4 >> 28) & 0xF);
+				toc_entry.resource_flags = value3;
+				toc_entry.resource_graphics_flags = value4;
+				toc_entry.size = get_size_from_flags(value3).saturating_add(get_size_from_flags(value4));
+			}
+			else
+			{
+				// a size in archive of 0 means the entry is stored uncompressed
+				toc_entry.size = value3;
+				toc_entry.encryption = value4;
+				toc_entry.is_compressed = size != 0;
+				toc_entry.size_in_archive = if size != 0 { size } else { value3 };
+			}
+		}
+		
+		toc_entries.push(toc_entry);
+	}
+	
+	Ok(toc_entries)
+}
+
+// resource memory size, from the system or graphics flags
+pub fn get_size_from_flags(flags: u32) -> u32
+{
+	let page_counts = ((flags >> 27) & 0x1)
+		+ (((flags >> 26) & 0x1) << 1)
+		+ (((flags >> 25) & 0x1) << 2)
+		+ (((flags >> 24) & 0x1) << 3)
+		+ (((flags >> 17) & 0x7F) << 4)
+		+ (((flags >> 11) & 0x3F) << 5)
+		+ (((flags >> 7) & 0xF) << 6)
+		+ (((flags >> 5) & 0x3) << 7)
+		+ (((flags >> 4) & 0x1) << 8);
+	let page_size = 0x200u32 << (flags & 0xF);
+	page_size.saturating_mul(page_counts)
+}
+
+// large resources
+pub fn get_large_resource_size(header: &[u8]) -> u32
+{
+	let mut size : u32 = 0;
+	for (i, seek) in LARGE_RESOURCE_SIZE_SEEKS.iter().enumerate()
+	{
+		size |= (header[*seek] as u32) << (i * 8);
+	}
+	size
+}
+
+// the size over the header of resource data that was just written at offset, then back to where the writer was
+pub fn write_large_resource_size<W: Write + Seek>(writer: &mut W, offset: u64, size: u32) -> Result<(), ImgError>
+{
+	let end = writer.stream_position()?;
+	for (i, seek) in LARGE_RESOURCE_SIZE_SEEKS.iter().enumerate()
+	{
+		writer.seek(SeekFrom::Start(offset + (*seek as u64)))?;
+		writer.write_all(&[(size >> (i * 8)) as u8])?;
+	}
+	writer.seek(SeekFrom::Start(end))?;
+	Ok(())
+}
+
+// a header with the size bytes of a large resource, "RS?7", put back together from the TOC
+pub fn is_large_resource_header(data: &[u8]) -> bool
+{
+	data.len() >= 16 && &data[0..2] == b"RS" && data[2] != b'C' && data[3] == b'7'
+}
+
+pub fn restore_large_resource_header(rpf: &super::super::entry::RpfEntry, resource_type: u32, data: &mut [u8])
+{
+	data[0..4].copy_from_slice(b"RSC7");
+	data[4..8].copy_from_slice(&resource_type.to_le_bytes());
+	data[8..12].copy_from_slice(&rpf.resource_flags.to_le_bytes());
+	data[12..16].copy_from_slice(&rpf.resource_graphics_flags.to_le_bytes());
+}
+
+// decrypt
+pub fn decrypt(keys: &Keystore, encryption: u32, mut buffer: Vec<u8>, name: &str, length: u32) -> Result<Vec<u8>, ImgError>
+{
+	if encryption == ENCRYPTION_AES
+	{
+		let aes = match crate::utility::AesEcb::new(keys.get_key(keystore::KEY_NAME_GTA_5_AES)?, 1)
+		{
+			Some(aes) => aes,
+			None => return Err(ImgError::DecryptFailed)
+		};
+		
+		// a remainder smaller than a block is not encrypted
+		aes.decrypt(&mut buffer);
+		return Ok(buffer);
+	}
+	
+	// NG, the key depends on the name and length of what is decrypted
+	let ng_keys = keys.get_key(keystore::KEY_NAME_GTA_5_NG_KEYS)?;
+	let ng_tables = keys.get_key(keystore::KEY_NAME_GTA_5_NG_TABLES)?;
+	if ng_keys.len() != 101 * 272
+	{
+		return Err(ImgError::DecryptFailed);
+	}
+	
+	let key_index = (crate::utility::get_jenkins_hash(name).wrapping_add(length).wrapping_add(61) % 101) as usize;
+	crate::utility::decrypt_ng(&mut buffer, &ng_keys[key_index*272..(key_index+1)*272], ng_tables)?;
+	Ok(buffer)
+}
+
+// entry data, resources keep their 16 byte RSC7 header unencrypted
+pub fn decrypt_file_data(rpf: &super::super::entry::RpfEntry, data: Vec<u8>, keys: &Keystore) -> Result<Vec<u8>, ImgError>
+{
+	if rpf.is_resource
+	{
+		if data.len() < 16
+		{
+			return Ok(data);
+		}
+		
+		let length = data.len() as u32;
+		let mut data2 = data[0..16].to_vec();
+		data2.extend(decrypt(keys, rpf.encryption, data[16..].to_vec(), &rpf.full_name, length)?);
+		Ok(data2)
+	}
+	else
+	{
+		decrypt(keys, rpf.encryption, data, &rpf.full_name, rpf.uncompressed_size)
+	}
+}
+
+// save, always as an unencrypted "OPEN" archive
+pub fn save_list(format: &mut super::super::Format, img_path_out: &str) -> Result<(), ImgError>
+{
+	let img_uses_temp_path_out : bool = format.img_path_in == img_path_out;
+	
+	let img_temp_path_out : String = crate::utility::get_next_file_path2(format.get_saving_dir()?, "temp.rpf".to_string());
+	let img_path_out2 = if img_uses_temp_path_out
+	{
+		img_temp_path_out
+	}
+	else
+	{
+		img_path_out.to_string()
+	};
+	
+	let toc_items = super::version2::get_toc_items(format, true);
+	
+	// name table, file records only have 16 bits for the name offset
+	let mut buffer_names = Vec::new();
+	let mut name_offsets = Vec::with_capacity(toc_items.len());
+	for toc_item in toc_items.iter()
+	{
+		if buffer_names.len() > 0xFFFF
+		{
+			return Err(ImgError::Unsupported(String::from("RPF version 7 name tables larger than 64 KB")));
+		}
+		
+		name_offsets.push(buffer_names.len() as u32);
+		buffer_names.extend(crate::name_codec::encode(&toc_item.name)?);
+		buffer_names.push(0);
+	}
+	buffer_names.resize(buffer_names.len().div_ceil(16) * 16, 0);
+	
+	let toc_size = 16 + (toc_items.len() as u64) * 16 + (buffer_names.len() as u64);
+	let data_offset = toc_size.div_ceil(512) * 512;
+	
+	let file_out = File::create(&img_path_out2)?;
+	let mut buffer_out = BufWriter::new(file_out);
+	
+	// entry data first, as compressing changes the sizes in the TOC
+	let img_is_new = format.is_new();
+	
+	let mut reader : Option<BufReader<File>> = None;
+	
+	if !img_is_new
+	{
+		reader = Some(BufReader::new(File::open(&format.img_path_in)?));
+	}
+	
+	let mut records : Vec<(u64, u32, u32)> = vec![(0, 0, 0); format.entries.len()];
+	
+	let mut seek = data_offset;
+	buffer_out.seek(SeekFrom::Start(seek))?;
+	
+	let mut entry_indices : Vec<usize> = (0..format.entries.len()).collect();
+	entry_indices.sort_by_key(|entry_index| format.entries[*entry_index].offset_out);
+	
+	for entry_index in entry_indices
+	{
+		let mut entry = format.entries[entry_index].clone();
+		let mut rpf = match &entry.rpf
+		{
+			Some(rpf) => rpf.clone(),
+			None => super::super::entry::RpfEntry
+			{
+				uncompressed_size: entry.get_data_size() as u32,
+				..Default::default()
+			}
+		};
+		
+		// entries that are decrypted or compressed while saving are loaded, the others are copied from their source as they are
+		let is_copied = rpf.encryption == 0 && (rpf.is_resource || rpf.is_compressed || !format.compress_entries);
+		let mut data : Vec<u8> = Vec::new();
+		let mut header : Vec<u8> = Vec::new();
+		
+		if is_copied
+		{
+			// replaced resources bring their own flags in the RSC7 header
+			if rpf.is_resource && !entry.data_temp_path.is_empty()
+			{
+				header = crate::utility::get_file_data_range(entry.data_temp_path.clone(), 0, 16)?;
+			}
+		}
+		else
+		{
+			data = if img_is_new
+			{
+				format.get_entry_data(&entry)?
+			}
+			else
+			{
+				entry.get_data_with_reader(&mut reader.as_mut().unwrap())?
+			};
+			
+			if rpf.encryption != 0
+			{
+				data = decrypt_file_data(&rpf, data, &format.keys)?;
+				rpf.encryption = ENCRYPTION_NONE;
+			}
+			
+			if rpf.is_resource
+			{
+				header = data[0..std::cmp::min(data.len(), 16)].to_vec();
+			}
+			else if format.compress_entries && !rpf.is_compressed
+			{
+				rpf.uncompressed_size = data.len() as u32;
+				data = crate::utility::compress_deflate(&data)?;
+				rpf.is_compressed = true;
+			}
+		}
+		
+		let data_size = if is_copied
+		{
+			entry.get_data_size()
+		}
+		else
+		{
+			data.len() as u64
+		};
+		let is_large_resource = rpf.is_resource && data_size >= LARGE_RESOURCE_SIZE;
+		
+		// the offset is stored in 512 byte blocks in 23 bits
+		if seek / 512 > 0x7FFFFF
+		{
+			return Err(ImgError::Unsupported(String::from("RPF version 7 entry offsets past 4 GB")));
+		}
+		
+		// name offset: 16 bits, size in archive: 24 bits, offset in 512 byte blocks: 23 bits, resource flag: 1 bit
+		let (size, value3, value4) = if rpf.is_resource
+		{
+			if header.len() >= 16 && &header[0..4] == b"RSC7"
+			{
+				rpf.resource_flags = u32::from_le_bytes(header[8..12].try_into().unwrap());
+				rpf.resource_graphics_flags = u32::from_le_bytes(header[12..16].try_into().unwrap());
+			}
+			
+			// large resources store their size in the RSC7 header, written after the data
+			(std::cmp::min(data_size, LARGE_RESOURCE_SIZE) as u32, rpf.resource_flags, rpf.resource_graphics_flags)
+		}
+		else
+		{
+			// a size in archive of 0 means the entry is stored uncompressed
+			if rpf.is_compressed
+			{
+				if data_size > 0xFFFFFF
+				{
+					return Err(ImgError::Unsupported(String::from("compressed RPF version 7 entries larger than 16 MB")));
+				}
+				(data_size as u32, rpf.uncompressed_size, 0)
+			}
+			else
+			{
+				(0, crate::utility::to_u32_field(data_size, "entry size")?, 0)
+			}
+		};
+		
+		let value = ((size as u64) << 16) | ((seek / 512) << 40) | (if rpf.is_resource { 1 << 63 } else { 0 });
+		records[entry_index] = (value, value3, value4);
+		
+		// entries read from the archive now match the saved archive, added entries keep their data file
+		format.entries[entry_index].offset_out = seek;
+		if format.entries[entry_index].data_temp_path.is_empty()
+		{
+			format.entries[entry_index].size = data_size;
+			format.entries[entry_index].rpf = Some(rpf);
+		}
+		
+		// copy or push entry data
+		if is_copied
+		{
+			if img_is_new
+			{
+				format.copy_entry_data(&entry, &mut buffer_out)?;
+			}
+			else
+			{
+				entry.copy_data_with_reader(reader.as_mut().unwrap(), &mut buffer_out)?;
+			}
+		}
+		else
+		{
+			buffer_out.write_all(data.as_slice())?;
+		}
+		
+		if is_large_resource
+		{
+			write_large_resource_size(&mut buffer_out, seek, crate::utility::to_u32_field(data_size, "resource size")?)?;
+		}
+		
+		// pad entry data
+		seek += data_size;
+		if seek % 512 != 0
+		{
+			let remainder = 512 - (seek % 512);
+			crate::utility::write_zeros_to(&mut buffer_out, remainder)?;
+			seek += remainder;
+		}
+	}
+	
+	// header
+	let mut buffer = Vec::with_capacity(data_offset as usize);
+	buffer.extend(b"7FPR");
+	buffer.extend(&(toc_items.len() as u32).to_le_bytes());
+	buffer.extend(&(buffer_names.len() as u32).to_le_bytes());
+	buffer.extend(&ENCRYPTION_OPEN.to_le_bytes());
+	
+	// TOC
+	for (i, toc_item) in toc_items.iter().enumerate()
+	{
+		match toc_item.entry_index
+		{
+			None =>
+			{
+				buffer.extend(&name_offsets[i].to_le_bytes());
+				buffer.extend(&0x7FFFFF00_u32.to_le_bytes());
+				buffer.extend(&toc_item.content_entry_index.to_le_bytes());
+				buffer.extend(&toc_item.content_entry_count.to_le_bytes());
+			},
+			Some(entry_index) =>
+			{
+				let (value, value3, value4) = records[entry_index];
+				buffer.extend(&(value | (name_offsets[i] as u64)).to_le_bytes());
+				buffer.extend(&value3.to_le_bytes());
+				buffer.extend(&value4.to_le_bytes());
+			}
+		}
+	}
+	
+	buffer.extend(buffer_names);
+	buffer.resize(data_offset as usize, 0);
+	
+	buffer_out.seek(SeekFrom::Start(0))?;
+	buffer_out.write_all(buffer.as_slice())?;
+	
+	buffer_out.flush()?;
+	drop(buffer_out);
+	
+	if img_uses_temp_path_out
+	{
+		crate::utility::move_file_over(&img_path_out2, img_path_out)?;
+	}
+	
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	
+	#[test]
+	fn aes_data_is_decrypted_with_one_round()
+	{
+		let key : Vec<u8> = (0..32).collect();
+		let mut keys = Keystore::default();
+		keys.add(keystore::KEY_NAME_GTA_5_AES, key.clone());
+		
+		let plaintext : Vec<u8> = (0..36).collect();
+		let mut buffer = plaintext.clone();
+		crate::utility::AesEcb::new(&key, 1).unwrap().encrypt(&mut buffer);
+		assert_ne!(buffer[0..32], plaintext[0..32]);
+		assert_eq!(buffer[32..], plaintext[32..]);
+		
+		assert_eq!(decrypt(&keys, ENCRYPTION_AES, buffer, "a.ysc", 36).unwrap(), plaintext);
+	}
+	
+	// with every table value 0 a block decrypts to the last round key, which tells which of the 101 keys was used
+	#[test]
+	fn ng_data_is_decrypted_with_the_key_for_its_name_and_length()
+	{
+		let mut ng_keys = Vec::with_capacity(101 * 272);
+		for key_index in 0..101
+		{
+			ng_keys.extend(vec![0; 256]);
+			ng_keys.extend(vec![key_index as u8; 16]);
+		}
+		let mut keys = Keystore::default();
+		keys.add(keystore::KEY_NAME_GTA_5_NG_KEYS, ng_keys);
+		keys.add(keystore::KEY_NAME_GTA_5_NG_TABLES, vec![0; 17 * 16 * 256 * 4]);
+		
+		// (jenkins("a.ysc") = 0x8DD1D92D, + 100 + 61) % 101 = 14
+		let buffer = decrypt(&keys, ENCRYPTION_NG, vec![0xAA; 20], "a.ysc", 100).unwrap();
+		assert_eq!(buffer[0..16], [14; 16]);
+		assert_eq!(buffer[16..], [0xAA; 4]);
+	}
+	
+	#[test]
+	fn ng_data_needs_both_keys()
+	{
+		let keys = Keystore::default();
+		assert!(matches!(decrypt(&keys, ENCRYPTION_NG, vec![0; 16], "a.ysc", 16), Err(ImgError::MissingKey(_))));
+	}
+	
+	// a resource one byte past what the TOC can hold, saved and parsed again
+	#[test]
+	fn large_resources_keep_their_size_in_the_header()
+	{
+		let path = std::env::temp_dir().join("imgdesk_test_rpf7_large_resource.rpf").to_string_lossy().to_string();
+		
+		let mut data = Vec::with_capacity((LARGE_RESOURCE_SIZE + 1) as usize);
+		data.extend(b"RSC7");
+		data.extend(&13u32.to_le_bytes());
+		data.extend(&0u32.to_le_bytes());
+		data.extend(&0xD0000000u32.to_le_bytes());
+		data.resize((LARGE_RESOURCE_SIZE + 1) as usize, 0x5A);
+		
+		let mut format = Format::default();
+		format.new("", "").unwrap();
+		format.format_type = FormatType::RPF;
+		format.img_version = 7;
+		format.add_data(String::from("a.ytd"), data.clone()).unwrap();
+		format.entries[0].rpf.as_mut().unwrap().is_resource = true;
+		format.recalculate_entry_offsets();
+		format.save(&path, "").unwrap();
+		format.reset();
+		
+		let mut format = Format::default();
+		format.parse(&path, "").unwrap();
+		let entry = format.entries[0].clone();
+		assert_eq!(entry.size, LARGE_RESOURCE_SIZE + 1);
+		assert_eq!(entry.resource_type, 13);
+		assert_eq!(format.get_entry_file_data(&entry).unwrap(), data);
+		format.reset();
+		
+		std::fs::remove_file(&path).unwrap();
+	}
+}
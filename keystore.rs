@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crypto::digest::Digest;
+use crypto::sha1::Sha1;
+
+use super::error::ImgError;
+
+// key names, by game and format
+pub const KEY_NAME_GTA_4 : &str = "gta4"; // GTA IV and EFLC, IMG version 3 and RPF2
+pub const KEY_NAME_MCLA : &str = "rpf3"; // Midnight Club: Los Angeles, RPF3
+pub const KEY_NAME_MP3 : &str = "rpf4"; // Max Payne 3, RPF4
+pub const KEY_NAME_RDR : &str = "rpf6"; // Red Dead Redemption, RPF6
+pub const KEY_NAME_GTA_5_AES : &str = "gta5_aes"; // GTA V, RPF7
+pub const KEY_NAME_GTA_5_NG_KEYS : &str = "gta5_ng_keys";
+pub const KEY_NAME_GTA_5_NG_TABLES : &str = "gta5_ng_tables";
+
+const CONFIG_FILE_NAME : &str = "keys.txt";
+
+// SHA-1 digests of keys that can be found in a game executable, by key name
+pub const KNOWN_KEY_DIGESTS : [(&str, [u8; 20]); 1] = [
+	(KEY_NAME_GTA_4, [ 0xde, 0xa3, 0x75, 0xef, 0x1e, 0x6e, 0xf2, 0x22, 0x3a, 0x12, 0x21, 0xc2, 0xc5, 0x75, 0xc4, 0x7b, 0xf1, 0x7e, 0xfa, 0x5e ])
+];
+
+// keys for encrypted archives, by name, none are built in, they come from keys.txt, a key file or a game executable
+#[derive(Clone, Default)]
+pub struct Keystore
+{
+	keys: HashMap<String, Vec<u8>>
+}
+
+impl Keystore
+{
+	pub fn get(&self, name: &str) -> Option<&Vec<u8>>
+	{
+		self.keys.get(name)
+	}
+	
+	// a key that is needed to continue
+	pub fn get_key(&self, name: &str) -> Result<&Vec<u8>, ImgError>
+	{
+		match self.keys.get(name)
+		{
+			Some(key) => Ok(key),
+			None => Err(ImgError::MissingKey(name.to_string()))
+		}
+	}
+	
+	pub fn add(&mut self, name: &str, key: Vec<u8>)
+	{
+		self.keys.insert(name.to_string(), key);
+	}
+	
+	// one "name = hex" key per line, lines starting with # are comments
+	pub fn add_from_file(&mut self, path: &str) -> Result<(), ImgError>
+	{
+		let data = super::utility::get_file_data(path.to_string())?;
+		for line in String::from_utf8_lossy(&data).lines()
+		{
+			let line = line.trim();
+			if line.is_empty() || line.starts_with('#')
+			{
+				continue;
+			}
+			
+			let (name, key_text) = match line.find('=')
+			{
+				Some(pos) => (line[0..pos].trim(), &line[pos+1..]),
+				None => return Err(ImgError::Unsupported(format!("key line \"{}\"", line)))
+			};
+			
+			match super::utility::parse_hex(key_text)
+			{
+				Some(key) => self.add(name, key),
+				None => return Err(ImgError::Unsupported(format!("key text for \"{}\"", name)))
+			}
+		}
+		Ok(())
+	}
+	
+	// config file in the user's data folder, in the same format as add_from_file
+	pub fn get_config_path() -> Option<String>
+	{
+		let base = super::format::get_local_data_dir()?;
+		Some(format!("{}{}", base, CONFIG_FILE_NAME))
+	}
+	
+	// keys from the config file, when there is one
+	pub fn add_from_config_file(&mut self) -> Result<(), ImgError>
+	{
+		let config_path = match Keystore::get_config_path()
+		{
+			Some(config_path) => config_path,
+			None => return Ok(())
+		};
+		if !Path::new(&config_path).is_file()
+		{
+			return Ok(());
+		}
+		self.add_from_file(&config_path)
+	}
+	
+	// add a key to the config file, so it is loaded next time, replacing the lines of a key with the same name
+	pub fn add_to_config_file(name: &str, key: &Vec<u8>) -> Result<(), ImgError>
+	{
+		let config_path = match Keystore::get_config_path()
+		{
+			Some(config_path) => config_path,
+			None => return Err(ImgError::Io(std::io::Error::new(std::io::ErrorKind::NotFound, "no local data folder to keep the key config file in")))
+		};
+		if let Some(parent) = Path::new(&config_path).parent()
+		{
+			fs::create_dir_all(parent)?;
+		}
+		
+		let mut key_text = String::with_capacity(key.len() * 2);
+		for byte in key.iter()
+		{
+			key_text.push_str(&format!("{:02X}", byte));
+		}
+		
+		let text = if Path::new(&config_path).is_file()
+		{
+			String::from_utf8_lossy(&super::utility::get_file_data(config_path.clone())?).to_string()
+		}
+		else
+		{
+			String::from("")
+		};
+		
+		fs::write(&config_path, set_key_line(&text, name, &format!("{} = {}", name, key_text)))?;
+		Ok(())
+	}
+	
+	// find known keys in a file the user owns, e.g. GTAIV.exe, and add them, returns the names of the keys found
+	pub fn add_from_executable(&mut self, path: &str) -> Result<Vec<String>, ImgError>
+	{
+		let data = super::utility::get_file_data(path.to_string())?;
+		let keys = find_keys(&data, &KNOWN_KEY_DIGESTS);
+		
+		let mut names = Vec::with_capacity(keys.len());
+		for (name, key) in keys
+		{
+			self.add(&name, key);
+			names.push(name);
+		}
+		Ok(names)
+	}
+}
+
+// name of the key on a "name = hex" line, None for comments and other lines
+fn get_key_line_name(line: &str) -> Option<&str>
+{
+	let line = line.trim();
+	if line.starts_with('#')
+	{
+		return None;
+	}
+	line.find('=').map(|pos| line[0..pos].trim())
+}
+
+// key file text with the key's line set, the key takes the place of its first line and other lines of the same key are removed
+pub fn set_key_line(text: &str, name: &str, key_line: &str) -> String
+{
+	let mut lines : Vec<&str> = Vec::new();
+	let mut is_replaced = false;
+	for line in text.lines()
+	{
+		if get_key_line_name(line) == Some(name)
+		{
+			if !is_replaced
+			{
+				lines.push(key_line);
+				is_replaced = true;
+			}
+			continue;
+		}
+		lines.push(line);
+	}
+	
+	if !is_replaced
+	{
+		lines.push(key_line);
+	}
+	
+	let mut text2 = lines.join("\n");
+	text2.push('\n');
+	text2
+}
+
+// 32 byte windows of the data whose SHA-1 digest matches a key digest, the first match for each key
+pub fn find_keys(data: &[u8], key_digests: &[(&str, [u8; 20])]) -> Vec<(String, Vec<u8>)>
+{
+	let mut keys : Vec<(String, Vec<u8>)> = Vec::new();
+	if data.len() < 32
+	{
+		return keys;
+	}
+	
+	let mut sha1 = Sha1::new();
+	let mut digest = [0; 20];
+	
+	for i in 0..(data.len() - 31)
+	{
+		let window = &data[i..i+32];
+		
+		sha1.reset();
+		sha1.input(window);
+		sha1.result(&mut digest);
+		
+		for (name, key_digest) in key_digests.iter()
+		{
+			if digest == *key_digest && !keys.iter().any(|(name2, _key)| name2 == name)
+			{
+				keys.push((name.to_string(), window.to_vec()));
+			}
+		}
+		
+		if keys.len() == key_digests.len()
+		{
+			break;
+		}
+	}
+	
+	keys
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	
+	#[test]
+	fn key_lines_are_replaced_instead_of_added_again()
+	{
+		let text = "# keys\nrpf4 = 00\ngta4 = 11\ngta4 = 22\n";
+		assert_eq!(set_key_line(text, "gta4", "gta4 = 33"), "# keys\nrpf4 = 00\ngta4 = 33\n");
+		assert_eq!(set_key_line(text, "rpf6", "rpf6 = 44"), "# keys\nrpf4 = 00\ngta4 = 11\ngta4 = 22\nrpf6 = 44\n");
+		assert_eq!(set_key_line("", "gta4", "gta4 = 33"), "gta4 = 33\n");
+		
+		// repeated runs give the same file
+		let text2 = set_key_line(text, "gta4", "gta4 = 33");
+		assert_eq!(set_key_line(&text2, "gta4", "gta4 = 33"), text2);
+	}
+	
+	#[test]
+	fn find_keys_matches_digests()
+	{
+		let mut data = vec![0u8; 100];
+		for (i, byte) in data[40..72].iter_mut().enumerate()
+		{
+			*byte = i as u8;
+		}
+		
+		let mut sha1 = Sha1::new();
+		let mut digest = [0; 20];
+		sha1.input(&data[40..72]);
+		sha1.result(&mut digest);
+		
+		let keys = find_keys(&data, &[("test", digest)]);
+		assert_eq!(keys, vec![(String::from("test"), data[40..72].to_vec())]);
+		assert!(find_keys(&data[0..60], &[("test", digest)]).is_empty());
+	}
+}
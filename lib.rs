@@ -0,0 +1,9 @@
+// code style: field names written out, explicit returns, &Vec and &String parameters, and % for alignment checks
+#![allow(clippy::redundant_field_names, clippy::needless_return, clippy::ptr_arg, clippy::manual_is_multiple_of)]
+
+pub mod error;
+pub mod format;
+pub mod keystore;
+pub mod name_codec;
+pub mod utility;
+pub mod vendor;
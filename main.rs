@@ -10,6 +10,12 @@ fn main()
 	
 	editor::load();
 	
+	// open an IMG passed on the command line, e.g. from a file association
+	if let Some(img_path_in) = std::env::args().nth(1)
+	{
+		editor::get_editor().open_directly(img_path_in);
+	}
+	
     nwg::dispatch_thread_events();
 }
 
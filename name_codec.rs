@@ -0,0 +1,107 @@
+use super::error::ImgError;
+
+// entry names are stored as Windows-1252 bytes, every byte maps to one character and back, so names round trip
+// the 5 bytes that Windows-1252 leaves undefined map to the control characters of the same value, as in Latin-1
+
+// characters for bytes 0x80 - 0x9F, the rest of the bytes are the same as Latin-1
+const CHARS_80_TO_9F : [char; 32] = [
+	'\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+	'\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+	'\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+	'\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}'
+];
+
+// decode
+pub fn decode_char(byte: u8) -> char
+{
+	if (0x80..=0x9F).contains(&byte)
+	{
+		CHARS_80_TO_9F[(byte - 0x80) as usize]
+	}
+	else
+	{
+		byte as char
+	}
+}
+
+// name up to the first zero byte, or all of the bytes when there is none
+pub fn decode(bytes: &[u8]) -> String
+{
+	let mut name = String::with_capacity(bytes.len());
+	for byte in bytes.iter()
+	{
+		if *byte == 0
+		{
+			break;
+		}
+		name.push(decode_char(*byte));
+	}
+	name
+}
+
+// encode
+pub fn encode_char(c: char) -> Option<u8>
+{
+	let value = c as u32;
+	if value < 0x80 || (0xA0..=0xFF).contains(&value)
+	{
+		return Some(value as u8);
+	}
+	
+	CHARS_80_TO_9F.iter().position(|c2| *c2 == c).map(|i| 0x80 + i as u8)
+}
+
+pub fn encode(name: &str) -> Result<Vec<u8>, ImgError>
+{
+	let mut bytes = Vec::with_capacity(name.len());
+	for c in name.chars()
+	{
+		match encode_char(c)
+		{
+			Some(byte) => bytes.push(byte),
+			None => return Err(ImgError::NameEncoding(name.to_string()))
+		}
+	}
+	Ok(bytes)
+}
+
+// length of the name once it is encoded, one byte per character
+pub fn get_encoded_len(name: &str) -> usize
+{
+	name.chars().count()
+}
+
+// validate
+// a name given by the user for a new or renamed entry, without its folder path
+pub fn check_name(name: &str) -> Result<(), ImgError>
+{
+	if name.is_empty() || name.chars().any(|c| c < ' ' || c == '\u{7F}' || c == '/' || c == '\\')
+	{
+		return Err(ImgError::InvalidName(name.to_string()));
+	}
+	encode(name)?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	
+	#[test]
+	fn every_byte_decodes_and_encodes_back()
+	{
+		let bytes : Vec<u8> = (1..=255).collect();
+		let name = decode(&bytes);
+		assert_eq!(get_encoded_len(&name), bytes.len());
+		assert_eq!(encode(&name).unwrap(), bytes);
+	}
+	
+	#[test]
+	fn names_end_at_the_first_zero_byte_and_unmapped_characters_are_rejected()
+	{
+		assert_eq!(decode(b"a.wdr\0b"), "a.wdr");
+		assert_eq!(encode("caf\u{E9}\u{20AC}").unwrap(), b"caf\xE9\x80");
+		assert!(matches!(encode("a\u{4E00}.wdr"), Err(ImgError::NameEncoding(_))));
+	}
+}
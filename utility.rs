@@ -0,0 +1,718 @@
+use std::convert::TryInto;
+use std::fs;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::Read;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::io;
+use std::ffi::OsStr;
+use std::path::Path;
+
+use num_format::{Locale, ToFormattedString};
+use flate2::read::DeflateDecoder;
+use flate2::read::ZlibDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use crypto::aes::KeySize;
+use crypto::symmetriccipher::BlockDecryptor;
+use crypto::symmetriccipher::BlockEncryptor;
+
+use super::error::ImgError;
+
+// file data
+pub fn set_file_data(path: String, data: &Vec<u8>) -> Result<(), ImgError>
+{
+	let mut f = File::create(&path)?;
+	f.write_all(data.as_slice())?;
+	Ok(())
+}
+
+pub fn set_file_data_no_overwrite(path: String, data: &[u8]) -> Result<(), ImgError>
+{
+	let mut f = File::create(get_next_file_path(path))?;
+	f.write_all(data)?;
+	Ok(())
+}
+
+pub fn set_file_data_overlap(path: String, data: &Vec<u8>, uses_temp_out_path: bool, temp_out_path: String) -> Result<(), ImgError>
+{
+	if uses_temp_out_path
+	{
+		let mut f = File::create(&temp_out_path)?;
+		f.write_all(data.as_slice())?;
+		move_file_over(&temp_out_path, &path)?;
+	}
+	else
+	{
+		let mut f = File::create(&path)?;
+		f.write_all(data.as_slice())?;
+	}
+	Ok(())
+}
+
+pub fn get_file_data(path: String) -> Result<Vec<u8>, ImgError>
+{
+	Ok(fs::read(&path)?)
+}
+
+pub fn get_file_data_with_meta(path: String) -> Result<(Vec<u8>, fs::Metadata), ImgError>
+{
+	let buffer = fs::read(&path)?;
+	let metadata = fs::metadata(&path)?;
+	Ok((buffer, metadata))
+}
+
+pub fn get_file_data_range(path: String, offset: u64, size: u64) -> Result<Vec<u8>, ImgError>
+{
+	let mut f = File::open(&path)?;
+	get_file_data_range_with_reader(&mut f, offset, size)
+}
+
+pub fn get_file_data_range_with_reader<R: Read + Seek>(reader: &mut R, offset: u64, size: u64) -> Result<Vec<u8>, ImgError>
+{
+	let mut buffer = Vec::new();
+	reader.seek(SeekFrom::Start(offset))?;
+	reader.take(size).read_to_end(&mut buffer)?;
+	
+	// the last entry in an archive is often not padded up to a whole sector
+	if (buffer.len() as u64) + 2048 <= size
+	{
+		return Err(ImgError::Io(io::Error::new(io::ErrorKind::UnexpectedEof, "entry data extends past the end of the file")));
+	}
+	buffer.resize(size as usize, 0);
+	
+	Ok(buffer)
+}
+
+// copy a range of a reader to a writer in blocks, without loading the range into memory
+// io::copy uses copy_file_range on Linux when both sides are files, so the data doesn't pass through this process
+pub fn copy_file_data_range_with_reader<R: Read + Seek, W: Write>(reader: &mut R, offset: u64, size: u64, writer: &mut W) -> Result<u64, ImgError>
+{
+	reader.seek(SeekFrom::Start(offset))?;
+	let copied = io::copy(&mut reader.by_ref().take(size), writer)?;
+	
+	// the last entry in an archive is often not padded up to a whole sector
+	if copied + 2048 <= size
+	{
+		return Err(ImgError::Io(io::Error::new(io::ErrorKind::UnexpectedEof, "entry data extends past the end of the file")));
+	}
+	write_zeros_to(writer, size - copied)?;
+	
+	Ok(size)
+}
+
+pub fn copy_file_data<W: Write>(path: String, writer: &mut W) -> Result<u64, ImgError>
+{
+	let mut f = File::open(&path)?;
+	Ok(io::copy(&mut f, writer)?)
+}
+
+pub fn get_reader_size<R: Seek>(reader: &mut R) -> Result<u64, ImgError>
+{
+	let seek = reader.stream_position()?;
+	let size = reader.seek(SeekFrom::End(0))?;
+	reader.seek(SeekFrom::Start(seek))?;
+	Ok(size)
+}
+
+// a byte range of another reader, e.g. an archive stored as an entry of another archive
+pub struct RangeReader<R: Read + Seek>
+{
+	reader: R,
+	offset: u64,
+	size: u64,
+	seek: u64
+}
+
+impl<R: Read + Seek> RangeReader<R>
+{
+	pub fn new(reader: R, offset: u64, size: u64) -> RangeReader<R>
+	{
+		RangeReader
+		{
+			reader: reader,
+			offset: offset,
+			size: size,
+			seek: 0
+		}
+	}
+}
+
+impl<R: Read + Seek> Read for RangeReader<R>
+{
+	fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize>
+	{
+		if self.seek >= self.size
+		{
+			return Ok(0);
+		}
+		
+		let len = std::cmp::min(buffer.len() as u64, self.size - self.seek) as usize;
+		self.reader.seek(SeekFrom::Start(self.offset + self.seek))?;
+		let read_len = self.reader.read(&mut buffer[0..len])?;
+		self.seek += read_len as u64;
+		Ok(read_len)
+	}
+}
+
+impl<R: Read + Seek> Seek for RangeReader<R>
+{
+	fn seek(&mut self, position: SeekFrom) -> io::Result<u64>
+	{
+		let seek = match position
+		{
+			SeekFrom::Start(seek) => seek as i64,
+			SeekFrom::End(seek) => (self.size as i64) + seek,
+			SeekFrom::Current(seek) => (self.seek as i64) + seek
+		};
+		
+		if seek < 0
+		{
+			return Err(io::Error::new(io::ErrorKind::InvalidInput, "seek before the start of the range"));
+		}
+		
+		self.seek = seek as u64;
+		Ok(self.seek)
+	}
+}
+
+pub fn move_file_over(path_from: &str, path_to: &str) -> Result<(), ImgError>
+{
+	if Path::new(path_to).is_file()
+	{
+		fs::remove_file(path_to)?;
+	}
+	fs::rename(path_from, path_to)?;
+	Ok(())
+}
+
+// file attributes
+pub fn get_file_size(path: String) -> u64
+{
+	let metadata = fs::metadata(&path);
+	match metadata
+	{
+		Err(_e1) =>
+		{
+			return 0;
+		},
+		Ok(r1) =>
+		{
+			return r1.len();
+		}
+	}
+}
+
+pub fn get_file_last_modified(path: String, default: std::time::SystemTime) -> std::time::SystemTime
+{
+	let metadata = fs::metadata(&path);
+	if metadata.is_err()
+	{
+		return default;
+	}
+	
+	let modified = metadata.unwrap().modified();
+	match modified
+	{
+		Err(_e2) =>
+		{
+			return default;
+		},
+		_ =>
+		{
+			return modified.unwrap();
+		}
+	};
+}
+
+// files in a folder and its sub folders, as paths relative to the folder with forward slashes, sorted
+pub fn get_file_paths_recursive(folder_path: &str) -> Result<Vec<String>, ImgError>
+{
+	let mut file_paths = Vec::new();
+	add_file_paths_recursive(Path::new(folder_path), "", &mut file_paths)?;
+	file_paths.sort();
+	Ok(file_paths)
+}
+
+fn add_file_paths_recursive(folder_path: &Path, relative_path: &str, file_paths: &mut Vec<String>) -> Result<(), ImgError>
+{
+	for dir_entry in fs::read_dir(folder_path)?
+	{
+		let dir_entry = dir_entry?;
+		let name = dir_entry.file_name().to_string_lossy().to_string();
+		if dir_entry.file_type()?.is_dir()
+		{
+			add_file_paths_recursive(&dir_entry.path(), &format!("{}{}/", relative_path, name), file_paths)?;
+		}
+		else
+		{
+			file_paths.push(format!("{}{}", relative_path, name));
+		}
+	}
+	Ok(())
+}
+
+// file path
+pub fn get_next_file_path(path_in: String) -> String
+{
+	let file_name : String = Path::new(&path_in).file_name().and_then(OsStr::to_str).unwrap().to_string();
+	let dir : String = path_in[0..(path_in.len()-file_name.len())].to_string();
+	
+	let mut counter : u64 = 1;
+	
+	let ext : String = super::vendor::get_extension_from_filename(&file_name).unwrap_or("").to_string();
+	let file_name_no_ext : String = if ext.is_empty()
+	{
+		file_name.clone()
+	}
+	else
+	{
+		file_name[0..file_name.len()-(ext.len()+1)].to_string()
+	};
+	
+	loop
+	{
+		let path : String = if counter == 1
+		{
+			format!("{}{}", dir, file_name)
+		}
+		else if ext.is_empty()
+		{
+			format!("{}{} ({})", dir, file_name_no_ext, counter)
+		}
+		else
+		{
+			format!("{}{} ({}).{}", dir, file_name_no_ext, counter, ext)
+		};
+		
+		if !Path::new(&path).exists()
+		{
+			return path;
+		}
+		
+		counter += 1;
+	}
+}
+
+pub fn get_next_file_path2(dir: String, file_name: String) -> String
+{
+	let mut file_path : String = dir.to_string();
+	let c : char = file_path.chars().last().unwrap();
+	if c != '/' && c != '\\'
+	{
+		file_path.push('/');
+	}
+	file_path.push_str(file_name.as_str());
+	
+	get_next_file_path(file_path)
+}
+
+// entry names can be longer than a file name may be, so files holding entry data use the start of the name and its extension
+pub fn get_data_file_name(entry_name: &str) -> String
+{
+	let stem : String = entry_name.chars().take(64).collect();
+	let ext : String = match Path::new(entry_name).extension().and_then(OsStr::to_str)
+	{
+		Some(ext) => ext.chars().take(16).collect(),
+		None => String::from("")
+	};
+	
+	if stem.len() == entry_name.len() || ext.is_empty()
+	{
+		stem
+	}
+	else
+	{
+		format!("{}.{}", stem, ext)
+	}
+}
+
+pub fn get_file_name(file_name: &str) -> Option<&str>
+{
+	Path::new(file_name)
+		.file_name()
+		.and_then(OsStr::to_str)
+}
+
+pub fn replace_file_extension(path: &str, ext: &str) -> Option<String>
+{
+	Some(Path::new(path).with_extension(ext).to_str().unwrap().to_string())
+}
+
+// string
+pub fn get_percent(partial_item_count: i32, total_item_count: i32) -> String
+{
+	if total_item_count == 0
+	{
+		return String::from("0");
+	}
+	else
+	{
+		let percent : i32 = ((partial_item_count as f64 / total_item_count as f64) * 100f64).floor() as i32;
+		if percent == 0 && partial_item_count > 0
+		{
+			return String::from("1");
+		}
+		else
+		{
+			return percent.to_formatted_string(&Locale::en);
+		}
+	}
+}
+
+pub fn get_null_string(buffer: &mut Vec<u8>, seek: u64) -> Result<String, ImgError>
+{
+	let start = seek as usize;
+	if start >= buffer.len()
+	{
+		return Err(ImgError::DirectoryOutOfRange);
+	}
+	
+	// decoding stops at the zero byte
+	Ok(super::name_codec::decode(&buffer[start..]))
+}
+
+// gta, the key comes from the keystore, the buffer is encrypted or decrypted in place
+pub fn encrypt_gta_4(key: &Vec<u8>, buffer: &mut [u8]) -> Result<(), ImgError>
+{
+	let aes = match AesEcb::new(key, 16)
+	{
+		Some(aes) => aes,
+		None => return Err(ImgError::EncryptFailed)
+	};
+	
+	aes.encrypt(buffer);
+	Ok(())
+}
+
+pub fn decrypt_gta_4(key: &Vec<u8>, buffer: &mut [u8]) -> Result<(), ImgError>
+{
+	decrypt_with_key(key, buffer, 16)
+}
+
+// user-supplied 256-bit key, GTA IV and later RAGE games use 16 rounds, GTA V uses 1
+pub fn decrypt_with_key(key: &Vec<u8>, buffer: &mut [u8], round_count: u64) -> Result<(), ImgError>
+{
+	let aes = match AesEcb::new(key, round_count)
+	{
+		Some(aes) => aes,
+		None => return Err(ImgError::DecryptFailed)
+	};
+	
+	aes.decrypt(buffer);
+	Ok(())
+}
+
+
+// gta 5
+// NG cipher, key is 17 round keys of 4 u32s, tables are 17 rounds of 16 tables of 256 u32s, a remainder smaller than a block is not encrypted
+pub fn decrypt_ng(buffer: &mut Vec<u8>, key: &[u8], tables: &[u8]) -> Result<(), ImgError>
+{
+	if key.len() != 17 * 16 || tables.len() != 17 * 16 * 256 * 4
+	{
+		return Err(ImgError::DecryptFailed);
+	}
+	
+	let get_key = |round: usize, i: usize| -> u32
+	{
+		let seek = (round * 16) + (i * 4);
+		u32::from_le_bytes(key[seek..seek+4].try_into().unwrap())
+	};
+	let get_table = |round: usize, table: usize, byte: u8| -> u32
+	{
+		let seek = (((round * 16) + table) * 256 + (byte as usize)) * 4;
+		u32::from_le_bytes(tables[seek..seek+4].try_into().unwrap())
+	};
+	
+	let block_count = buffer.len() / 16;
+	for i in 0..block_count
+	{
+		let block = &mut buffer[i*16..(i+1)*16];
+		for round in 0..17
+		{
+			// rounds 0, 1 and 16 mix each column, the others mix across columns
+			let order : [usize; 16] = if round <= 1 || round == 16
+			{
+				[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]
+			}
+			else
+			{
+				[0, 7, 10, 13, 1, 4, 11, 14, 2, 5, 8, 15, 3, 6, 9, 12]
+			};
+			
+			let mut block_out = [0u8; 16];
+			for column in 0..4
+			{
+				let mut value = get_key(round, column);
+				for j in 0..4
+				{
+					let table = order[(column * 4) + j];
+					value ^= get_table(round, table, block[table]);
+				}
+				block_out[column*4..(column+1)*4].copy_from_slice(&value.to_le_bytes());
+			}
+			block.copy_from_slice(&block_out);
+		}
+	}
+	Ok(())
+}
+
+// key text, e.g. "1AB56F..."
+pub fn parse_hex(text: &str) -> Option<Vec<u8>>
+{
+	let text = text.trim();
+	if text.len() % 2 != 0
+	{
+		return None;
+	}
+	
+	let mut bytes = Vec::with_capacity(text.len() / 2);
+	for i in (0..text.len()).step_by(2)
+	{
+		match u8::from_str_radix(text.get(i..i+2)?, 16)
+		{
+			Ok(byte) => bytes.push(byte),
+			Err(_) => return None
+		}
+	}
+	Some(bytes)
+}
+
+// aes, 256-bit key in ECB mode, each block encrypted round_count times, in place and without allocating
+// a remainder smaller than a block is not encrypted
+pub struct AesEcb
+{
+	encryptor: Box<dyn BlockEncryptor>,
+	decryptor: Box<dyn BlockDecryptor>,
+	round_count: u64
+}
+
+impl AesEcb
+{
+	// None when the key isn't 32 bytes
+	pub fn new(key: &[u8], round_count: u64) -> Option<AesEcb>
+	{
+		if key.len() != 32
+		{
+			return None;
+		}
+		
+		let (encryptor, decryptor) = new_aes_256(key);
+		Some(AesEcb
+		{
+			encryptor: encryptor,
+			decryptor: decryptor,
+			round_count: round_count
+		})
+	}
+	
+	pub fn encrypt(&self, buffer: &mut [u8])
+	{
+		let mut block = [0; 16];
+		for chunk in buffer.chunks_exact_mut(16)
+		{
+			for _i in 0..self.round_count
+			{
+				block.copy_from_slice(chunk);
+				self.encryptor.encrypt_block(&block, chunk);
+			}
+		}
+	}
+	
+	pub fn decrypt(&self, buffer: &mut [u8])
+	{
+		let mut block = [0; 16];
+		for chunk in buffer.chunks_exact_mut(16)
+		{
+			for _i in 0..self.round_count
+			{
+				block.copy_from_slice(chunk);
+				self.decryptor.decrypt_block(&block, chunk);
+			}
+		}
+	}
+}
+
+// AES-NI when the CPU has it
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn new_aes_256(key: &[u8]) -> (Box<dyn BlockEncryptor>, Box<dyn BlockDecryptor>)
+{
+	if crypto::util::supports_aesni()
+	{
+		return (Box::new(crypto::aesni::AesNiEncryptor::new(KeySize::KeySize256, key)), Box::new(crypto::aesni::AesNiDecryptor::new(KeySize::KeySize256, key)));
+	}
+	(Box::new(crypto::aessafe::AesSafe256Encryptor::new(key)), Box::new(crypto::aessafe::AesSafe256Decryptor::new(key)))
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+fn new_aes_256(key: &[u8]) -> (Box<dyn BlockEncryptor>, Box<dyn BlockDecryptor>)
+{
+	(Box::new(crypto::aessafe::AesSafe256Encryptor::new(key)), Box::new(crypto::aessafe::AesSafe256Decryptor::new(key)))
+}
+
+// compression
+pub fn decompress_zlib(buffer_in: &Vec<u8>) -> Result<Vec<u8>, ImgError>
+{
+	let mut buffer_out = Vec::new();
+	let mut decoder = ZlibDecoder::new(buffer_in.as_slice());
+	decoder.read_to_end(&mut buffer_out)?;
+	Ok(buffer_out)
+}
+
+// zlib or raw deflate, which has no header, raw deflate can start like a zlib header by chance
+pub fn decompress_zlib_or_deflate(buffer_in: &Vec<u8>) -> Result<Vec<u8>, ImgError>
+{
+	if buffer_in.len() >= 2 && buffer_in[0] == 0x78 && ((buffer_in[0] as u16) * 256 + (buffer_in[1] as u16)) % 31 == 0
+	{
+		if let Ok(buffer_out) = decompress_zlib(buffer_in)
+		{
+			return Ok(buffer_out);
+		}
+	}
+	
+	let mut buffer_out = Vec::new();
+	let mut decoder = DeflateDecoder::new(buffer_in.as_slice());
+	decoder.read_to_end(&mut buffer_out)?;
+	Ok(buffer_out)
+}
+
+// raw deflate, without a zlib header
+pub fn compress_deflate(buffer_in: &Vec<u8>) -> Result<Vec<u8>, ImgError>
+{
+	let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+	encoder.write_all(buffer_in.as_slice())?;
+	Ok(encoder.finish()?)
+}
+
+// hash
+// Jenkins one-at-a-time hash of the lower case text, as used for RAGE names, over the bytes the name is stored as
+pub fn get_jenkins_hash(text: &str) -> u32
+{
+	let mut hash : u32 = 0;
+	for c in text.to_lowercase().chars()
+	{
+		let c = super::name_codec::encode_char(c).unwrap_or(b'?');
+		hash = hash.wrapping_add(c as u32);
+		hash = hash.wrapping_add(hash << 10);
+		hash ^= hash >> 6;
+	}
+	hash = hash.wrapping_add(hash << 3);
+	hash ^= hash >> 11;
+	hash = hash.wrapping_add(hash << 15);
+	hash
+}
+
+// directory
+pub fn check_directory_range(file_size: u64, directory_offset: u64, entry_count: u64, entry_size: u64) -> Result<(), ImgError>
+{
+	let directory_size = match entry_count.checked_mul(entry_size)
+	{
+		Some(size) => size,
+		None => return Err(ImgError::DirectoryOutOfRange)
+	};
+	
+	if directory_offset + directory_size > file_size
+	{
+		return Err(ImgError::DirectoryOutOfRange);
+	}
+	
+	Ok(())
+}
+
+// buffer
+pub fn write_zeros(buffer: &mut Vec<u8>, zero_count: u64)
+{
+	buffer.resize(buffer.len() + (zero_count as usize), 0);
+}
+
+// write zeros to a writer a block at a time, for padding between entries
+pub fn write_zeros_to<W: Write>(writer: &mut W, zero_count: u64) -> Result<(), ImgError>
+{
+	let zeros = [0u8; 2048];
+	let mut remaining = zero_count;
+	while remaining > 0
+	{
+		let count = std::cmp::min(remaining, zeros.len() as u64);
+		writer.write_all(&zeros[0..(count as usize)])?;
+		remaining -= count;
+	}
+	Ok(())
+}
+
+// sectors
+pub fn to_sectors(size: u64) -> u64
+{
+	return (size / 2048) + (if size % 2048 == 0 { 0 } else { 1 });
+}
+
+pub fn sectors_to_bytes(bytes: u64) -> u64
+{
+	return bytes * 2048;
+}
+
+pub fn to_sector_bytes(bytes: u64) -> u64
+{
+	return sectors_to_bytes(to_sectors(bytes));
+}
+
+// directory fields, values that don't fit are an error instead of wrapping
+pub fn to_u16_field(value: u64, field_name: &str) -> Result<u16, ImgError>
+{
+	if value > u16::MAX as u64
+	{
+		return Err(ImgError::ValueTooLarge(field_name.to_string(), value));
+	}
+	Ok(value as u16)
+}
+
+pub fn to_u32_field(value: u64, field_name: &str) -> Result<u32, ImgError>
+{
+	if value > u32::MAX as u64
+	{
+		return Err(ImgError::ValueTooLarge(field_name.to_string(), value));
+	}
+	Ok(value as u32)
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	
+	#[test]
+	fn fields_reject_values_that_overflow()
+	{
+		assert_eq!(to_u16_field(u16::MAX as u64, "size").unwrap(), u16::MAX);
+		assert!(matches!(to_u16_field(u16::MAX as u64 + 1, "size"), Err(ImgError::ValueTooLarge(_, 65536))));
+		
+		assert_eq!(to_u32_field(u32::MAX as u64, "offset").unwrap(), u32::MAX);
+		assert!(matches!(to_u32_field(u32::MAX as u64 + 1, "offset"), Err(ImgError::ValueTooLarge(_, 4294967296))));
+	}
+	
+	// FIPS-197 appendix C.3, AES-256
+	#[test]
+	fn aes_matches_the_known_vector_and_round_trips_in_place()
+	{
+		let key : Vec<u8> = (0..32).collect();
+		let plaintext = parse_hex("00112233445566778899AABBCCDDEEFF").unwrap();
+		let ciphertext = parse_hex("8EA2B7CA516745BFEAFC49904B496089").unwrap();
+		
+		let mut buffer = plaintext.clone();
+		AesEcb::new(&key, 1).unwrap().encrypt(&mut buffer);
+		assert_eq!(buffer, ciphertext);
+		decrypt_with_key(&key, &mut buffer, 1).unwrap();
+		assert_eq!(buffer, plaintext);
+		
+		// 16 rounds, and a remainder smaller than a block is left as it is
+		let mut buffer = plaintext.clone();
+		buffer.extend(&[1, 2, 3]);
+		encrypt_gta_4(&key, &mut buffer).unwrap();
+		assert_ne!(buffer[0..16], plaintext[..]);
+		assert_eq!(buffer[16..], [1, 2, 3]);
+		decrypt_gta_4(&key, &mut buffer).unwrap();
+		assert_eq!(buffer[0..16], plaintext[..]);
+		
+		assert!(matches!(decrypt_gta_4(&vec![0; 16], &mut buffer), Err(ImgError::DecryptFailed)));
+	}
+}
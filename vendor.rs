@@ -16,10 +16,3 @@ where
     <A as AsMut<[T]>>::as_mut(&mut a).clone_from_slice(slice);
     a
 }
-
-pub unsafe fn str_from_u8_nul_utf8_unchecked(utf8_src: &[u8]) -> &str {
-    let nul_range_end = utf8_src.iter()
-        .position(|&c| c == b'\0')
-        .unwrap_or(utf8_src.len()); // default to length if no `\0` present
-    ::std::str::from_utf8_unchecked(&utf8_src[0..nul_range_end])
-}
\ No newline at end of file